@@ -117,7 +117,7 @@ mod tests {
         let signing_key = generate_signing_key(&hss_parameter, None);
 
         b.iter(|| {
-            let mut signing_key = signing_key.clone();
+            let mut signing_key = signing_key.duplicate();
             signing_key.try_sign(&MESSAGE).unwrap()
         });
     }
@@ -133,7 +133,7 @@ mod tests {
         let signing_key = generate_signing_key(&hss_parameter, Some(aux_slice));
 
         b.iter(|| {
-            let mut signing_key = signing_key.clone();
+            let mut signing_key = signing_key.duplicate();
             signing_key
                 .try_sign_with_aux(&MESSAGE, Some(aux_slice))
                 .unwrap()
@@ -151,7 +151,7 @@ mod tests {
         let signing_key = generate_signing_key(&hss_parameter, Some(aux_slice));
 
         b.iter(|| {
-            let mut signing_key = signing_key.clone();
+            let mut signing_key = signing_key.duplicate();
             signing_key
                 .try_sign_with_aux(&MESSAGE, Some(aux_slice))
                 .unwrap()
@@ -169,7 +169,7 @@ mod tests {
         let signing_key = generate_signing_key(&hss_parameter, Some(aux_slice));
 
         b.iter(|| {
-            let mut signing_key = signing_key.clone();
+            let mut signing_key = signing_key.duplicate();
             signing_key
                 .try_sign_with_aux(&MESSAGE, Some(aux_slice))
                 .unwrap()
@@ -185,7 +185,7 @@ mod tests {
         let signing_key = generate_signing_key(&hss_parameter, None);
 
         b.iter(|| {
-            let mut signing_key = signing_key.clone();
+            let mut signing_key = signing_key.duplicate();
             signing_key.try_sign(&MESSAGE).unwrap()
         });
     }
@@ -201,7 +201,7 @@ mod tests {
         let signing_key = generate_signing_key(&hss_parameter, Some(aux_slice));
 
         b.iter(|| {
-            let mut signing_key = signing_key.clone();
+            let mut signing_key = signing_key.duplicate();
             signing_key
                 .try_sign_with_aux(&MESSAGE, Some(aux_slice))
                 .unwrap()
@@ -5,7 +5,7 @@ fn main() {
     let dest_path = Path::new(&out_dir).join("constants.rs");
     let mut f = File::create(dest_path).expect("Could not create file");
 
-    #[cfg(feature = "fast_verify")]
+    #[cfg(feature = "fast_verify_core")]
     {
         let max_hash_optimizations = option_env!("HBS_LMS_MAX_HASH_OPTIMIZATIONS");
         let max_hash_optimizations: usize = max_hash_optimizations
@@ -20,7 +20,7 @@ fn main() {
         println!("cargo:rerun-if-env-changed=HBS_LMS_MAX_HASH_OPTIMIZATIONS");
     }
 
-    #[cfg(all(feature = "fast_verify", feature = "std"))]
+    #[cfg(feature = "fast_verify")]
     {
         let threads = option_env!("HBS_LMS_THREADS");
         let threads = threads
@@ -198,6 +198,7 @@ fn sign_mut(args: &ArgMatches) -> Result<(), std::io::Error> {
             &private_key_data,
             &mut private_key_update_function,
             Some(aux_slice),
+            &FastVerifyOptions::default(),
         )
     } else {
         hbs_lms::sign_mut::<Hasher>(
@@ -205,6 +206,7 @@ fn sign_mut(args: &ArgMatches) -> Result<(), std::io::Error> {
             &private_key_data,
             &mut private_key_update_function,
             None,
+            &FastVerifyOptions::default(),
         )
     };
 
@@ -217,11 +219,19 @@ fn sign_mut(args: &ArgMatches) -> Result<(), std::io::Error> {
     write(&signature_name_mut, signature.as_ref())?;
     write(&message_name_mut, &message_data)?;
 
+    if let Some(verify_chain_steps) = signature.verify_chain_steps {
+        println!(
+            "Verification takes {} hash chain steps.",
+            verify_chain_steps
+        );
+    }
     #[cfg(feature = "verbose")]
     println!(
         "fast_verify needed {} iterations.",
         signature.hash_iterations
     );
+    #[cfg(feature = "verbose")]
+    println!("Hash operations: {:?}", signature.hash_operations);
 
     Ok(())
 }
@@ -306,7 +316,8 @@ fn genkey(args: &ArgMatches) -> Result<(), Box<dyn std::error::Error>> {
         return DemoError::raise("Seed was not given".to_string());
     };
 
-    let mut aux_data = vec![0u8; genkey_parameter.aux_data];
+    let aux_data_info = aux_data_info_for_budget(&parameter, genkey_parameter.aux_data);
+    let mut aux_data = vec![0u8; aux_data_info.size()];
     let aux_slice: &mut &mut [u8] = &mut &mut aux_data[..];
 
     let (signing_key, verifying_key) = keygen(&parameter, &seed, Some(aux_slice))
@@ -319,7 +330,14 @@ fn genkey(args: &ArgMatches) -> Result<(), Box<dyn std::error::Error>> {
     write(&aux_name, aux_slice)?;
 
     write(public_key_filename.as_str(), verifying_key.as_slice())?;
-    write(private_key_filename.as_str(), signing_key.as_slice())?;
+    write(private_key_filename.as_str(), signing_key.expose_secret())?;
+
+    println!(
+        "Aux data: {} bytes caching levels {:?} ({}x fewer top tree leaf generations per signature)",
+        aux_data_info.size(),
+        aux_data_info.levels().collect::<Vec<_>>(),
+        aux_data_info.sign_speedup()
+    );
 
     Ok(())
 }
@@ -20,12 +20,24 @@ pub const D_MESG: [u8; 2] = [0x81, 0x81];
 pub const D_LEAF: [u8; 2] = [0x82, 0x82];
 pub const D_INTR: [u8; 2] = [0x83, 0x83];
 
+// I || q (or r) || D_* prefix of the PBLC, MESG, LEAF and INTR hashes
+pub const DOMAIN_PREFIX_LEN: usize = ILEN + 4 + 2;
+
 pub const TOPSEED_SEED: usize = 23;
 pub const TOPSEED_LEN: usize = TOPSEED_SEED + 32;
 pub const TOPSEED_D: usize = 20;
 pub const TOPSEED_WHICH: usize = 22;
 pub const D_TOPSEED: u16 = 0xfefe;
 
+// Derivation of seeds from a master secret and a label path, see `hss_derive_seed`
+pub const HDSEED_D: usize = 20;
+pub const HDSEED_WHICH: usize = 22;
+pub const HDSEED_PREFIX_LEN: usize = 23;
+pub const D_HDSEED: u16 = 0xfcfc;
+pub const HDSEED_MASTER: u8 = 0x00;
+pub const HDSEED_CHILD: u8 = 0x01;
+pub const HDSEED_SEED: u8 = 0x02;
+
 pub const PRNG_I: usize = 0;
 pub const PRNG_Q: usize = 16;
 pub const PRNG_J: usize = 20;
@@ -43,14 +55,30 @@ pub const LMS_LEAF_IDENTIFIERS_SIZE: usize = 8;
 pub const REF_IMPL_MAX_ALLOWED_HSS_LEVELS: usize = 8;
 pub const REF_IMPL_MAX_PRIVATE_KEY_SIZE: usize =
     LMS_LEAF_IDENTIFIERS_SIZE + REF_IMPL_MAX_ALLOWED_HSS_LEVELS + MAX_SEED_LEN;
+pub const EXTENDED_PARAMETER_SET_SIZE: usize = 2 * REF_IMPL_MAX_ALLOWED_HSS_LEVELS;
+pub const EXTENDED_PRIVATE_KEY_SIZE: usize =
+    LMS_LEAF_IDENTIFIERS_SIZE + EXTENDED_PARAMETER_SET_SIZE + MAX_SEED_LEN;
+// Start and end of the signature indices a shard of a private key is restricted to
+pub const SHARD_RANGE_SIZE: usize = 2 * LMS_LEAF_IDENTIFIERS_SIZE;
+pub const MAX_PRIVATE_KEY_SIZE: usize = EXTENDED_PRIVATE_KEY_SIZE + SHARD_RANGE_SIZE;
+// Creation time of a backup key, followed by the key
+pub const MAX_BACKUP_KEY_SIZE: usize = 8 + MAX_PRIVATE_KEY_SIZE;
+// Private key of a delegated tree, the verify chain steps and the signed public key of the tree
+pub const MAX_DELEGATED_PRIVATE_KEY_SIZE: usize =
+    EXTENDED_PRIVATE_KEY_SIZE + ILEN + 2 + MAX_HSS_SIGNED_PUBLIC_KEY_LENGTH;
 
 pub const MAX_HASH_SIZE: usize = 32;
+
+// Randomizers tried for fast verifiable signed public keys of new keys. The count is stored in the
+// private key, as the signatures are recomputed on every expansion of the key with it.
+pub const SIGNED_PUBLIC_KEY_HASH_OPTIMIZATIONS: usize = 1 << 10;
 pub const MAX_HASH_BLOCK_SIZE: usize = 64;
 
 pub const PRNG_MAX_LEN: usize = prng_len(MAX_HASH_SIZE);
 
 pub const MAX_HASH_CHAIN_COUNT: usize =
     get_hash_chain_count(MIN_WINTERNITZ_PARAMETER, MAX_HASH_SIZE);
+pub const MAX_HASH_CHAIN_BATCH_SIZE: usize = 8;
 
 pub const MAX_LMOTS_SIGNATURE_LENGTH: usize =
     lmots_signature_length(MAX_HASH_SIZE, MAX_HASH_CHAIN_COUNT);
@@ -148,6 +176,22 @@ pub const DAUX_D: usize = 20;
 pub const DAUX_PREFIX_LEN: usize = 22; /* Not counting the seed value */
 pub const D_DAUX: u16 = 0xfdfd;
 
+pub const STATE_D: usize = 20;
+pub const STATE_PREFIX_LEN: usize = 22; /* Not counting the fingerprint and the private key */
+pub const D_STATE: u16 = 0xfbfb;
+
+pub const AUDIT_D: usize = 20;
+pub const AUDIT_PREFIX_LEN: usize = 22; /* Not counting the previous chain hash and the entry */
+pub const D_AUDIT: u16 = 0xfafa;
+
+pub const RANDOMIZER_D: usize = 20;
+pub const RANDOMIZER_PREFIX_LEN: usize = 22; /* Not counting the previous randomizer */
+pub const D_RANDOMIZER: u16 = 0xf9f9;
+
+pub const PREHASH_D: usize = 20;
+pub const PREHASH_PREFIX_LEN: usize = 22; /* Not counting the digest */
+pub const D_PREHASH: u16 = 0xf8f8;
+
 pub mod winternitz_chain {
     use super::MAX_HASH_SIZE;
 
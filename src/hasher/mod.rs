@@ -6,11 +6,15 @@ use core::{
 use digest::{FixedOutput, Update};
 use tinyvec::ArrayVec;
 
-use crate::constants::{winternitz_chain::*, MAX_HASH_SIZE};
+use crate::constants::{winternitz_chain::*, MAX_HASH_CHAIN_BATCH_SIZE, MAX_HASH_SIZE};
 
+pub mod operations;
 pub mod sha256;
 pub mod shake256;
 
+pub use operations::HashOperations;
+
+#[derive(Default)]
 pub struct HashChainData {
     data: ArrayVec<[u8; ITER_MAX_LEN]>,
 }
@@ -88,4 +92,169 @@ pub trait HashChain:
             hc_data[ITER_PREV..].copy_from_slice(temp_hash.as_slice());
         }
     }
+
+    /**
+     * Advances up to `MAX_HASH_CHAIN_BATCH_SIZE` independent hash chains, where `hc_data[k]` is
+     * iterated from `from[k]` to `to[k]` like in [`HashChain::do_actual_hash_chain`]. All three
+     * slices must have the same length, otherwise this panics.
+     *
+     * Implementations can override this to advance the chains in lock-step, e.g. with
+     * multi-buffer SIMD hashing. The default implementation advances one chain after another.
+     * */
+    fn do_actual_hash_chains(
+        &mut self,
+        hc_data: &mut [HashChainData],
+        from: &[usize],
+        to: &[usize],
+    ) {
+        assert_eq!(hc_data.len(), from.len());
+        assert_eq!(hc_data.len(), to.len());
+        assert!(hc_data.len() <= MAX_HASH_CHAIN_BATCH_SIZE);
+
+        for ((hc_data, &from), &to) in hc_data.iter_mut().zip(from).zip(to) {
+            self.do_actual_hash_chain(hc_data, from, to);
+        }
+    }
+}
+
+/**
+ * Marker for the hash functions with the full output size of 256 bits. Only these are collision
+ * resistant enough to sign the digest of a message instead of the message itself, see
+ * [`crate::SigningKey::try_sign_digest`].
+ *
+ * ```compile_fail
+ * use hbs_lms::{Sha256_192, SigningKey};
+ *
+ * fn sign_digest(signing_key: &mut SigningKey<Sha256_192>, digest: Sha256_192) {
+ *     let _ = signing_key.try_sign_digest(digest);
+ * }
+ * ```
+ * */
+pub trait FullWidthHashChain: HashChain {}
+
+/// Computes `hash_chain_count` hash chains of one LM-OTS key in batches using
+/// [`HashChain::do_actual_hash_chains`]. `chain_input` returns the initial value and the range
+/// of iterations of a chain, `chain_output` receives the results in order of the chains.
+pub(crate) fn do_hash_chains_batched<'a, H: HashChain>(
+    hasher: &mut H,
+    lms_tree_identifier: &[u8],
+    lms_leaf_identifier: &[u8],
+    hash_chain_count: u16,
+    chain_input: impl Fn(u16) -> (&'a [u8], usize, usize),
+    mut chain_output: impl FnMut(ArrayVec<[u8; MAX_HASH_SIZE]>),
+    hash_operations: &mut HashOperations,
+) {
+    let step_compressions = HashOperations::compressions::<H>(iter_len(H::OUTPUT_SIZE as usize));
+    let mut hc_data: [HashChainData; MAX_HASH_CHAIN_BATCH_SIZE] = Default::default();
+    let mut from = [0usize; MAX_HASH_CHAIN_BATCH_SIZE];
+    let mut to = [0usize; MAX_HASH_CHAIN_BATCH_SIZE];
+
+    for batch_start in (0..hash_chain_count).step_by(MAX_HASH_CHAIN_BATCH_SIZE) {
+        let batch_len = core::cmp::min(
+            MAX_HASH_CHAIN_BATCH_SIZE,
+            (hash_chain_count - batch_start) as usize,
+        );
+
+        for (k, hash_chain_id) in (batch_start..batch_start + batch_len as u16).enumerate() {
+            let (initial_value, chain_from, chain_to) = chain_input(hash_chain_id);
+
+            hc_data[k] = H::prepare_hash_chain_data(lms_tree_identifier, lms_leaf_identifier);
+            hc_data[k][ITER_K..ITER_J].copy_from_slice(&hash_chain_id.to_be_bytes());
+            hc_data[k][ITER_PREV..].copy_from_slice(initial_value);
+            from[k] = chain_from;
+            to[k] = chain_to;
+            hash_operations
+                .add_chain(chain_to.saturating_sub(chain_from) as u64 * step_compressions);
+        }
+
+        hasher.do_actual_hash_chains(
+            &mut hc_data[..batch_len],
+            &from[..batch_len],
+            &to[..batch_len],
+        );
+
+        for hc_data in hc_data[..batch_len].iter() {
+            chain_output(ArrayVec::try_from(&hc_data[ITER_PREV..]).unwrap());
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::{rngs::OsRng, RngCore};
+    use tinyvec::ArrayVec;
+
+    use crate::{
+        constants::{MAX_HASH_CHAIN_COUNT, MAX_HASH_SIZE},
+        hasher::{sha256::Sha256_256, shake256::Shake256_192},
+    };
+
+    use super::{do_hash_chains_batched, HashChain, HashChainData, HashOperations};
+
+    fn compare_with_single_chains<H: HashChain>(hash_chain_count: u16) {
+        let mut lms_tree_identifier = [0u8; 16];
+        let mut initial_values = [[0u8; MAX_HASH_SIZE]; MAX_HASH_CHAIN_COUNT];
+        OsRng.fill_bytes(&mut lms_tree_identifier);
+        for initial_value in initial_values.iter_mut() {
+            OsRng.fill_bytes(initial_value);
+        }
+        let lms_leaf_identifier = 7u32.to_be_bytes();
+        let output_size = H::OUTPUT_SIZE as usize;
+        let range = |i: u16| (i as usize % 5, 3 + i as usize % 13);
+
+        let mut batched: ArrayVec<[ArrayVec<[u8; MAX_HASH_SIZE]>; MAX_HASH_CHAIN_COUNT]> =
+            ArrayVec::new();
+        let mut hash_operations = HashOperations::default();
+        do_hash_chains_batched(
+            &mut H::default(),
+            &lms_tree_identifier,
+            &lms_leaf_identifier,
+            hash_chain_count,
+            |i| {
+                let (from, to) = range(i);
+                (&initial_values[i as usize][..output_size], from, to)
+            },
+            |result| batched.push(result),
+            &mut hash_operations,
+        );
+
+        assert_eq!(batched.len(), hash_chain_count as usize);
+        #[cfg(feature = "verbose")]
+        {
+            let steps: usize = (0..hash_chain_count)
+                .map(|i| range(i).1.saturating_sub(range(i).0))
+                .sum();
+            assert_eq!(hash_operations.chain, steps as u64);
+        }
+        for i in 0..hash_chain_count {
+            let (from, to) = range(i);
+            let mut hc_data =
+                H::prepare_hash_chain_data(&lms_tree_identifier, &lms_leaf_identifier);
+            let single = H::default().do_hash_chain(
+                &mut hc_data,
+                i,
+                &initial_values[i as usize][..output_size],
+                from,
+                to,
+            );
+            assert_eq!(batched[i as usize], single);
+        }
+    }
+
+    #[test]
+    fn batched_hash_chains_sha256() {
+        compare_with_single_chains::<Sha256_256>(67);
+    }
+
+    #[test]
+    fn batched_hash_chains_shake256() {
+        compare_with_single_chains::<Shake256_192>(26);
+    }
+
+    #[test]
+    #[should_panic]
+    fn batched_hash_chains_length_mismatch() {
+        let mut hc_data: [HashChainData; 2] = Default::default();
+        Sha256_256::default().do_actual_hash_chains(&mut hc_data, &[0, 0], &[1]);
+    }
 }
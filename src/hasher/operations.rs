@@ -0,0 +1,117 @@
+use core::ops::{Add, AddAssign};
+
+use super::HashChain;
+
+/**
+ * Number of compression function calls spent by a single operation (key generation, signing or
+ * verification), split by what the hashes were used for.
+ *
+ * The counts assume a Merkle–Damgård padding with a block size of [`HashChain::BLOCK_SIZE`],
+ * i.e. they are exact for SHA-256 and an approximation for SHAKE256.
+ */
+#[cfg(feature = "verbose")]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct HashOperations {
+    /// Steps of the LM-OTS Winternitz chains
+    pub chain: u64,
+    /// LM-OTS public key compressions and LMS leaf nodes
+    pub leaf: u64,
+    /// LMS interior nodes
+    pub interior_node: u64,
+    /// Derivation of LM-OTS private keys, seeds, tree identifiers and signature randomizers
+    pub seed_derive: u64,
+    /// Message hashes, including the signed public keys of lower HSS levels
+    pub message: u64,
+}
+
+/// Without the `verbose` feature nothing is counted
+#[cfg(not(feature = "verbose"))]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct HashOperations {}
+
+#[cfg(feature = "verbose")]
+impl HashOperations {
+    pub fn total(&self) -> u64 {
+        self.chain + self.leaf + self.interior_node + self.seed_derive + self.message
+    }
+
+    pub(crate) fn add_chain(&mut self, compressions: u64) {
+        self.chain += compressions;
+    }
+
+    pub(crate) fn add_leaf(&mut self, compressions: u64) {
+        self.leaf += compressions;
+    }
+
+    pub(crate) fn add_interior_node(&mut self, compressions: u64) {
+        self.interior_node += compressions;
+    }
+
+    pub(crate) fn add_seed_derive(&mut self, compressions: u64) {
+        self.seed_derive += compressions;
+    }
+
+    pub(crate) fn add_message(&mut self, compressions: u64) {
+        self.message += compressions;
+    }
+}
+
+#[cfg(not(feature = "verbose"))]
+impl HashOperations {
+    pub(crate) fn add_chain(&mut self, _compressions: u64) {}
+
+    pub(crate) fn add_leaf(&mut self, _compressions: u64) {}
+
+    pub(crate) fn add_interior_node(&mut self, _compressions: u64) {}
+
+    pub(crate) fn add_seed_derive(&mut self, _compressions: u64) {}
+
+    pub(crate) fn add_message(&mut self, _compressions: u64) {}
+}
+
+impl HashOperations {
+    pub(crate) fn compressions<H: HashChain>(input_len: usize) -> u64 {
+        // One byte of padding and eight bytes of length field are appended to the input
+        ((input_len + 8) / H::BLOCK_SIZE as usize + 1) as u64
+    }
+}
+
+impl Add for HashOperations {
+    type Output = Self;
+
+    fn add(mut self, other: Self) -> Self {
+        self += other;
+        self
+    }
+}
+
+#[cfg(feature = "verbose")]
+impl AddAssign for HashOperations {
+    fn add_assign(&mut self, other: Self) {
+        self.chain += other.chain;
+        self.leaf += other.leaf;
+        self.interior_node += other.interior_node;
+        self.seed_derive += other.seed_derive;
+        self.message += other.message;
+    }
+}
+
+#[cfg(not(feature = "verbose"))]
+impl AddAssign for HashOperations {
+    fn add_assign(&mut self, _other: Self) {}
+}
+
+#[cfg(test)]
+mod tests {
+    use super::HashOperations;
+    use crate::hasher::sha256::Sha256_256;
+
+    #[test]
+    fn compressions_follow_sha256_padding() {
+        assert_eq!(HashOperations::compressions::<Sha256_256>(0), 1);
+        assert_eq!(HashOperations::compressions::<Sha256_256>(55), 1);
+        assert_eq!(HashOperations::compressions::<Sha256_256>(56), 2);
+        assert_eq!(HashOperations::compressions::<Sha256_256>(119), 2);
+        assert_eq!(HashOperations::compressions::<Sha256_256>(120), 3);
+    }
+}
@@ -2,13 +2,70 @@ use core::convert::TryFrom;
 use tinyvec::ArrayVec;
 
 use sha2::{
-    digest::{typenum::U32, FixedOutput, FixedOutputReset, Output, OutputSizeUser, Reset, Update},
+    compress256,
+    digest::{
+        generic_array::GenericArray,
+        typenum::{U32, U64},
+        FixedOutput, FixedOutputReset, HashMarker, Output, OutputSizeUser, Reset, Update,
+    },
     Sha256 as Hasher,
 };
 
-use crate::constants::MAX_HASH_SIZE;
+use crate::constants::{winternitz_chain::*, MAX_HASH_SIZE};
+
+use super::{FullWidthHashChain, HashChain, HashChainData};
+
+const SHA256_BLOCK_SIZE: usize = 64;
+const SHA256_LENGTH_OFFSET: usize = SHA256_BLOCK_SIZE - 8;
+const SHA256_INITIAL_STATE: [u32; 8] = [
+    0x6a09e667, 0xbb67ae85, 0x3c6ef372, 0xa54ff53a, 0x510e527f, 0x9b05688c, 0x1f83d9ab, 0x5be0cd19,
+];
+
+/**
+ * Hash chain using the SHA-256 compression function directly.
+ *
+ * Every step of a hash chain hashes `I || q || i || j || tmp`, which always fits into a single
+ * block together with the SHA-256 padding. Therefore, the padded block is prepared once and only
+ * `j` and `tmp` are updated in place, avoiding the buffering and finalization of [`sha2::Sha256`].
+ * Chain data that does not fit into a single block is hashed with the generic implementation.
+ */
+fn do_sha256_hash_chain<H: HashChain>(hc_data: &mut HashChainData, from: usize, to: usize) {
+    let len = hc_data.len();
+    let output_size = H::OUTPUT_SIZE as usize;
+
+    // The block needs room for the 0x80 padding byte and the 64-bit message length
+    if len >= SHA256_LENGTH_OFFSET {
+        let mut hasher = H::default();
+        for j in from..to {
+            hc_data[ITER_J] = j as u8;
+            hasher.update(hc_data);
+            let temp_hash = hasher.finalize_reset();
+            hc_data[ITER_PREV..].copy_from_slice(temp_hash.as_slice());
+        }
+        return;
+    }
+
+    let mut block = GenericArray::<u8, U64>::default();
+    block[..len].copy_from_slice(hc_data);
+    block[len] = 0x80;
+    block[SHA256_LENGTH_OFFSET..].copy_from_slice(&(8 * len as u64).to_be_bytes());
+
+    for j in from..to {
+        block[ITER_J] = j as u8;
+
+        let mut state = SHA256_INITIAL_STATE;
+        compress256(&mut state, core::slice::from_ref(&block));
 
-use super::HashChain;
+        for (chunk, word) in block[ITER_PREV..ITER_PREV + output_size]
+            .chunks_exact_mut(4)
+            .zip(state.iter())
+        {
+            chunk.copy_from_slice(&word.to_be_bytes());
+        }
+    }
+
+    hc_data.copy_from_slice(&block[..len]);
+}
 
 macro_rules! define_sha {
     ($name:ident, $output_size:expr) => {
@@ -35,6 +92,15 @@ macro_rules! define_sha {
                 )
                 .unwrap()
             }
+
+            fn do_actual_hash_chain(
+                &mut self,
+                hc_data: &mut HashChainData,
+                from: usize,
+                to: usize,
+            ) {
+                do_sha256_hash_chain::<Self>(hc_data, from, to);
+            }
         }
 
         impl OutputSizeUser for $name {
@@ -65,6 +131,8 @@ macro_rules! define_sha {
             }
         }
 
+        impl HashMarker for $name {}
+
         impl PartialEq for $name {
             fn eq(&self, _: &Self) -> bool {
                 false
@@ -75,6 +143,85 @@ macro_rules! define_sha {
 
 define_sha!(Sha256_256, 32);
 
+impl FullWidthHashChain for Sha256_256 {}
+
 define_sha!(Sha256_192, 24);
 
 define_sha!(Sha256_128, 16);
+
+#[cfg(test)]
+mod tests {
+    use rand::{rngs::OsRng, RngCore};
+    use tinyvec::ArrayVec;
+
+    use crate::{constants::MAX_HASH_SIZE, hasher::HashChain};
+
+    use super::{Sha256_128, Sha256_192, Sha256_256};
+
+    // Plain hash chain as implemented by the default method of `HashChain`
+    fn reference_hash_chain<H: HashChain>(
+        lms_tree_identifier: &[u8],
+        lms_leaf_identifier: &[u8],
+        hash_chain_id: u16,
+        initial_value: &[u8],
+        from: usize,
+        to: usize,
+    ) -> ArrayVec<[u8; MAX_HASH_SIZE]> {
+        let mut hasher = H::default();
+        let mut tmp: ArrayVec<[u8; MAX_HASH_SIZE]> = initial_value.iter().copied().collect();
+
+        for j in from..to {
+            hasher.update(lms_tree_identifier);
+            hasher.update(lms_leaf_identifier);
+            hasher.update(&hash_chain_id.to_be_bytes());
+            hasher.update(&[j as u8]);
+            hasher.update(tmp.as_slice());
+            tmp = hasher.finalize_reset();
+        }
+        tmp
+    }
+
+    fn compare_with_reference<H: HashChain>() {
+        let mut lms_tree_identifier = [0u8; 16];
+        let mut lms_leaf_identifier = [0u8; 4];
+        let mut initial_value = [0u8; 32];
+
+        for (from, to) in [(0, 0), (0, 1), (0, 255), (3, 15), (254, 255)] {
+            OsRng.fill_bytes(&mut lms_tree_identifier);
+            OsRng.fill_bytes(&mut lms_leaf_identifier);
+            OsRng.fill_bytes(&mut initial_value);
+            let initial_value = &initial_value[..H::OUTPUT_SIZE as usize];
+
+            let mut hc_data =
+                H::prepare_hash_chain_data(&lms_tree_identifier, &lms_leaf_identifier);
+            let result = H::default().do_hash_chain(&mut hc_data, 42, initial_value, from, to);
+
+            assert_eq!(
+                result,
+                reference_hash_chain::<H>(
+                    &lms_tree_identifier,
+                    &lms_leaf_identifier,
+                    42,
+                    initial_value,
+                    from,
+                    to
+                )
+            );
+        }
+    }
+
+    #[test]
+    fn hash_chain_sha256_256() {
+        compare_with_reference::<Sha256_256>();
+    }
+
+    #[test]
+    fn hash_chain_sha256_192() {
+        compare_with_reference::<Sha256_192>();
+    }
+
+    #[test]
+    fn hash_chain_sha256_128() {
+        compare_with_reference::<Sha256_128>();
+    }
+}
@@ -3,14 +3,14 @@ use tinyvec::ArrayVec;
 use sha3::{
     digest::{
         typenum::U32, ExtendableOutput, ExtendableOutputReset, FixedOutput, FixedOutputReset,
-        Output, OutputSizeUser, Reset, Update, XofReader,
+        HashMarker, Output, OutputSizeUser, Reset, Update, XofReader,
     },
     Shake256 as Hasher,
 };
 
 use crate::constants::MAX_HASH_SIZE;
 
-use super::HashChain;
+use super::{FullWidthHashChain, HashChain};
 
 macro_rules! define_shake {
     ($name:ident, $output_size:expr) => {
@@ -67,6 +67,8 @@ macro_rules! define_shake {
             }
         }
 
+        impl HashMarker for $name {}
+
         impl PartialEq for $name {
             fn eq(&self, _: &Self) -> bool {
                 false
@@ -77,6 +79,8 @@ macro_rules! define_shake {
 
 define_shake!(Shake256_256, 32);
 
+impl FullWidthHashChain for Shake256_256 {}
+
 define_shake!(Shake256_192, 24);
 
 define_shake!(Shake256_128, 16);
@@ -0,0 +1,561 @@
+use crate::{
+    constants::{AUDIT_D, AUDIT_PREFIX_LEN, D_AUDIT, MAX_ALLOWED_HSS_LEVELS, MAX_HASH_SIZE},
+    hasher::HashChain,
+    hss::signing::InMemoryHssSignature,
+    util::helper::read_and_advance,
+};
+
+use core::convert::TryInto;
+use signature::Error;
+use tinyvec::ArrayVec;
+
+#[cfg(feature = "std")]
+use std::{
+    fs,
+    io::{self, Write},
+    path::PathBuf,
+    vec::Vec,
+};
+#[cfg(feature = "std")]
+use subtle::ConstantTimeEq;
+
+// Index, timestamp, level count, leaf indices, message digest and chain hash
+pub const MAX_AUDIT_LOG_ENTRY_SIZE: usize =
+    8 + 8 + 1 + 4 * MAX_ALLOWED_HSS_LEVELS + 2 * MAX_HASH_SIZE;
+
+/**
+ * Record of a single signature in an [`AuditLog`].
+ *
+ * `index` is the global signature index and `leaf_indices` are the LMS leaf indices of all HSS
+ * levels, starting with the top level. The chain hash covers the entry and the chain hash of the
+ * previous entry:
+ * `chain_hash = H(0^20 || D_AUDIT || previous_chain_hash || index || timestamp || level count ||
+ * leaf_indices || message_digest)` with `D_AUDIT = 0xfafa`, starting from an all-zero chain hash.
+ */
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct AuditLogEntry {
+    pub index: u64,
+    pub leaf_indices: ArrayVec<[u32; MAX_ALLOWED_HSS_LEVELS]>,
+    /// Hash of the signed message with the hash function of the key
+    pub message_digest: ArrayVec<[u8; MAX_HASH_SIZE]>,
+    /// Time of the signature as supplied by the signer, e.g. seconds since the Unix epoch
+    pub timestamp: u64,
+    pub chain_hash: ArrayVec<[u8; MAX_HASH_SIZE]>,
+}
+
+impl AuditLogEntry {
+    /// Entry for `signature` of `message`, chained to the entry with `previous_chain_hash`
+    pub fn new<H: HashChain>(
+        previous_chain_hash: Option<&[u8]>,
+        message: &[u8],
+        signature: &[u8],
+        timestamp: u64,
+    ) -> Result<Self, Error> {
+        let (index, leaf_indices) = signature_indices::<H>(signature).ok_or_else(Error::new)?;
+
+        let mut entry = Self {
+            index,
+            leaf_indices,
+            message_digest: H::default().chain(message).finalize(),
+            timestamp,
+            chain_hash: ArrayVec::new(),
+        };
+        entry.chain_hash = entry.compute_chain_hash::<H>(previous_chain_hash);
+
+        Ok(entry)
+    }
+
+    fn compute_chain_hash<H: HashChain>(
+        &self,
+        previous_chain_hash: Option<&[u8]>,
+    ) -> ArrayVec<[u8; MAX_HASH_SIZE]> {
+        let mut prefix = [0u8; AUDIT_PREFIX_LEN];
+
+        prefix[AUDIT_D] = (D_AUDIT >> 8) as u8;
+        prefix[AUDIT_D + 1] = (D_AUDIT & 0xff) as u8;
+
+        let mut hasher = H::default().chain(prefix);
+        match previous_chain_hash {
+            Some(previous_chain_hash) => hasher.update(previous_chain_hash),
+            None => hasher.update(&[0u8; MAX_HASH_SIZE][..H::OUTPUT_SIZE as usize]),
+        }
+        let bytes = self.to_bytes();
+        hasher
+            .chain(&bytes[..bytes.len() - self.chain_hash.len()])
+            .finalize()
+    }
+
+    pub fn to_bytes(&self) -> ArrayVec<[u8; MAX_AUDIT_LOG_ENTRY_SIZE]> {
+        let mut result = ArrayVec::new();
+
+        result.extend_from_slice(&self.index.to_be_bytes());
+        result.extend_from_slice(&self.timestamp.to_be_bytes());
+        result.push(self.leaf_indices.len() as u8);
+        for leaf_index in self.leaf_indices.iter() {
+            result.extend_from_slice(&leaf_index.to_be_bytes());
+        }
+        result.extend_from_slice(&self.message_digest);
+        result.extend_from_slice(&self.chain_hash);
+
+        result
+    }
+
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, Error> {
+        if bytes.len() < 8 + 8 + 1 || bytes.len() > MAX_AUDIT_LOG_ENTRY_SIZE {
+            return Err(Error::new());
+        }
+
+        let mut index = 0;
+        let signature_index =
+            u64::from_be_bytes(read_and_advance(bytes, 8, &mut index).try_into().unwrap());
+        let timestamp =
+            u64::from_be_bytes(read_and_advance(bytes, 8, &mut index).try_into().unwrap());
+        let levels = read_and_advance(bytes, 1, &mut index)[0] as usize;
+        if levels == 0 || levels > MAX_ALLOWED_HSS_LEVELS || bytes.len() < index + 4 * levels {
+            return Err(Error::new());
+        }
+        let leaf_indices = (0..levels)
+            .map(|_| u32::from_be_bytes(read_and_advance(bytes, 4, &mut index).try_into().unwrap()))
+            .collect();
+
+        // The message digest and the chain hash have the same length
+        let hashes = &bytes[index..];
+        if hashes.is_empty() || hashes.len() % 2 != 0 {
+            return Err(Error::new());
+        }
+        let (message_digest, chain_hash) = hashes.split_at(hashes.len() / 2);
+
+        Ok(Self {
+            index: signature_index,
+            leaf_indices,
+            message_digest: message_digest.iter().copied().collect(),
+            timestamp,
+            chain_hash: chain_hash.iter().copied().collect(),
+        })
+    }
+}
+
+/// Global signature index and LMS leaf indices of all levels of an HSS signature
+fn signature_indices<H: HashChain>(
+    signature: &[u8],
+) -> Option<(u64, ArrayVec<[u32; MAX_ALLOWED_HSS_LEVELS]>)> {
+    let signature = InMemoryHssSignature::<H>::new(signature)?;
+
+    let mut index = 0u64;
+    let mut leaf_indices = ArrayVec::new();
+    let lms_signatures = signature
+        .signed_public_keys
+        .iter()
+        .map(|signed_public_key| signed_public_key.as_ref().map(|signed| &signed.sig))
+        .chain(core::iter::once(Some(&signature.signature)));
+
+    for lms_signature in lms_signatures {
+        let lms_signature = lms_signature?;
+        let tree_height = lms_signature.lms_parameter.get_tree_height();
+
+        index =
+            index.checked_shl(tree_height.into())? | u64::from(lms_signature.lms_leaf_identifier);
+        leaf_indices.push(lms_signature.lms_leaf_identifier);
+    }
+
+    Some((index, leaf_indices))
+}
+
+/**
+ * Append-only storage of [`AuditLogEntry`]s, see [`crate::SigningKey::try_sign_with_audit_log`].
+ * One log is used per signing key.
+ *
+ * Logging is opt-in: only [`crate::SigningKey::try_sign_with_audit_log`] appends to the log.
+ * Signatures created in any other way, e.g. with [`crate::sign`], [`crate::begin_sign`] or
+ * [`signature::SignerMut`], are not logged and are reported as
+ * [`AuditLogError::UnloggedSignature`] by [`hss_verify_audit_log`].
+ */
+pub trait AuditLog {
+    /// Chain hash of the last entry, `None` if the log is empty
+    fn last_chain_hash(&mut self) -> Result<Option<ArrayVec<[u8; MAX_HASH_SIZE]>>, Error>;
+
+    fn append(&mut self, entry: &AuditLogEntry) -> Result<(), Error>;
+}
+
+/**
+ * [`AuditLog`] in memory, e.g. to forward the entries to another storage.
+ */
+#[cfg(feature = "std")]
+#[derive(Clone, Debug, Default)]
+pub struct InMemoryAuditLog {
+    pub entries: Vec<AuditLogEntry>,
+}
+
+#[cfg(feature = "std")]
+impl AuditLog for InMemoryAuditLog {
+    fn last_chain_hash(&mut self) -> Result<Option<ArrayVec<[u8; MAX_HASH_SIZE]>>, Error> {
+        Ok(self.entries.last().map(|entry| entry.chain_hash))
+    }
+
+    fn append(&mut self, entry: &AuditLogEntry) -> Result<(), Error> {
+        self.entries.push(entry.clone());
+        Ok(())
+    }
+}
+
+/**
+ * [`AuditLog`] stored in a file, to which each entry is appended with a leading two byte length.
+ * The chain hash of the last entry is read once and then cached, hence the file must not be
+ * appended to by anything else while it is used by this log.
+ */
+#[cfg(feature = "std")]
+#[derive(Clone, Debug)]
+pub struct FileAuditLog {
+    path: PathBuf,
+    last_chain_hash: Option<Option<ArrayVec<[u8; MAX_HASH_SIZE]>>>,
+}
+
+#[cfg(feature = "std")]
+impl FileAuditLog {
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self {
+            path: path.into(),
+            last_chain_hash: None,
+        }
+    }
+
+    pub fn entries(&self) -> Result<Vec<AuditLogEntry>, Error> {
+        let bytes = match fs::read(&self.path) {
+            Ok(bytes) => bytes,
+            Err(error) if error.kind() == io::ErrorKind::NotFound => return Ok(Vec::new()),
+            Err(_) => return Err(Error::new()),
+        };
+
+        let mut entries = Vec::new();
+        let mut index = 0;
+        while index < bytes.len() {
+            if bytes.len() < index + 2 {
+                return Err(Error::new());
+            }
+            let entry_len =
+                u16::from_be_bytes(read_and_advance(&bytes, 2, &mut index).try_into().unwrap());
+            if bytes.len() < index + entry_len as usize {
+                return Err(Error::new());
+            }
+            entries.push(AuditLogEntry::from_bytes(read_and_advance(
+                &bytes,
+                entry_len as usize,
+                &mut index,
+            ))?);
+        }
+
+        Ok(entries)
+    }
+}
+
+#[cfg(feature = "std")]
+impl AuditLog for FileAuditLog {
+    fn last_chain_hash(&mut self) -> Result<Option<ArrayVec<[u8; MAX_HASH_SIZE]>>, Error> {
+        if let Some(last_chain_hash) = self.last_chain_hash {
+            return Ok(last_chain_hash);
+        }
+
+        let last_chain_hash = self.entries()?.last().map(|entry| entry.chain_hash);
+        self.last_chain_hash = Some(last_chain_hash);
+
+        Ok(last_chain_hash)
+    }
+
+    fn append(&mut self, entry: &AuditLogEntry) -> Result<(), Error> {
+        let bytes = entry.to_bytes();
+
+        let mut file = fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.path)
+            .map_err(|_| Error::new())?;
+        let result = file
+            .write_all(&(bytes.len() as u16).to_be_bytes())
+            .and_then(|_| file.write_all(&bytes))
+            .and_then(|_| file.sync_all())
+            .map_err(|_| Error::new());
+
+        // A partially written entry is only detected by reading the file again
+        self.last_chain_hash = match result {
+            Ok(()) => Some(Some(entry.chain_hash)),
+            Err(_) => None,
+        };
+
+        result
+    }
+}
+
+/**
+ * Reason why an audit log was rejected by [`hss_verify_audit_log`].
+ */
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum AuditLogError {
+    /// The chain hash of the entry at this position of the log does not match
+    BrokenChain { position: usize },
+    /// The signature index was logged or used by different signatures more than once
+    DuplicateIndex { index: u64 },
+    /// The signature index is lower than the one of the previous entry
+    OutOfOrder { index: u64 },
+    /// A signature was not logged
+    UnloggedSignature { index: u64 },
+    /// The leaf indices of a signature differ from the logged ones
+    LeafIndexMismatch { index: u64 },
+    /// A signature could not be parsed
+    InvalidSignature,
+}
+
+impl core::fmt::Display for AuditLogError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            AuditLogError::BrokenChain { position } => {
+                write!(f, "audit log chain is broken at entry {}", position)
+            }
+            AuditLogError::DuplicateIndex { index } => {
+                write!(f, "signature index {} is used more than once", index)
+            }
+            AuditLogError::OutOfOrder { index } => {
+                write!(f, "signature index {} is out of order", index)
+            }
+            AuditLogError::UnloggedSignature { index } => {
+                write!(f, "signature with index {} is not logged", index)
+            }
+            AuditLogError::LeafIndexMismatch { index } => {
+                write!(f, "leaf indices of signature {} differ from the log", index)
+            }
+            AuditLogError::InvalidSignature => f.write_str("invalid signature"),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for AuditLogError {}
+
+impl From<AuditLogError> for Error {
+    fn from(_: AuditLogError) -> Self {
+        Error::new()
+    }
+}
+
+/**
+ * Check the chain hashes of a complete audit log and that every signature index is logged only
+ * once. Each of `signatures` must be logged with its leaf indices, and different signatures must
+ * not share a signature index, which would reveal a reused leaf.
+ */
+#[cfg(feature = "std")]
+pub fn hss_verify_audit_log<H: HashChain>(
+    entries: &[AuditLogEntry],
+    signatures: &[&[u8]],
+) -> Result<(), AuditLogError> {
+    let mut previous: Option<&AuditLogEntry> = None;
+    for (position, entry) in entries.iter().enumerate() {
+        let chain_hash =
+            entry.compute_chain_hash::<H>(previous.map(|previous| previous.chain_hash.as_slice()));
+        if !bool::from(chain_hash.ct_eq(&entry.chain_hash)) {
+            return Err(AuditLogError::BrokenChain { position });
+        }
+
+        match previous {
+            Some(previous) if previous.index == entry.index => {
+                return Err(AuditLogError::DuplicateIndex { index: entry.index })
+            }
+            Some(previous) if previous.index > entry.index => {
+                return Err(AuditLogError::OutOfOrder { index: entry.index })
+            }
+            _ => {}
+        }
+        previous = Some(entry);
+    }
+
+    let mut indexed_signatures = signatures
+        .iter()
+        .map(|signature| {
+            signature_indices::<H>(signature)
+                .map(|(index, leaf_indices)| (index, leaf_indices, *signature))
+                .ok_or(AuditLogError::InvalidSignature)
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+    indexed_signatures.sort_unstable_by_key(|(index, _, _)| *index);
+
+    let mut previous: Option<(u64, &[u8])> = None;
+    for (index, leaf_indices, signature) in indexed_signatures {
+        let entry = entries
+            .binary_search_by_key(&index, |entry| entry.index)
+            .map(|position| &entries[position])
+            .map_err(|_| AuditLogError::UnloggedSignature { index })?;
+        if entry.leaf_indices != leaf_indices {
+            return Err(AuditLogError::LeafIndexMismatch { index });
+        }
+
+        // Sorted by index, hence signatures with the same index are adjacent
+        if let Some((previous_index, previous_signature)) = previous {
+            if previous_index == index && previous_signature != signature {
+                return Err(AuditLogError::DuplicateIndex { index });
+            }
+        }
+        previous = Some((index, signature));
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        hss::hss_keygen, util::helper::test_helper::gen_random_seed, HssParameter, LmotsAlgorithm,
+        LmsAlgorithm, Sha256_256,
+    };
+
+    use digest::Update;
+    use signature::SignerMut;
+
+    type H = Sha256_256;
+
+    fn parameters() -> [HssParameter<H>; 2] {
+        [
+            HssParameter::new(LmotsAlgorithm::LmotsW4, LmsAlgorithm::LmsH5),
+            HssParameter::new(LmotsAlgorithm::LmotsW4, LmsAlgorithm::LmsH5),
+        ]
+    }
+
+    #[test]
+    fn audit_log_entry() {
+        let message = [1u8, 2, 3];
+        let (mut signing_key, _) =
+            hss_keygen::<H>(&parameters(), &gen_random_seed::<H>(), None).unwrap();
+
+        for _ in 0..33 {
+            signing_key.try_sign(&message).unwrap();
+        }
+        let signature = signing_key.try_sign(&message).unwrap();
+
+        let entry = AuditLogEntry::new::<H>(None, &message, signature.as_ref(), 42).unwrap();
+        assert_eq!(entry.index, 33);
+        assert_eq!(entry.leaf_indices.as_slice(), [1, 1]);
+        assert_eq!(entry.message_digest, H::default().chain(message).finalize());
+        assert_eq!(AuditLogEntry::from_bytes(&entry.to_bytes()).unwrap(), entry);
+
+        assert!(AuditLogEntry::new::<H>(None, &message, &signature.as_ref()[1..], 42).is_err());
+
+        #[cfg(feature = "std")]
+        {
+            let signatures: [&[u8]; 1] = [signature.as_ref()];
+            assert!(hss_verify_audit_log::<H>(core::slice::from_ref(&entry), &signatures).is_ok());
+
+            let chained_entry = AuditLogEntry::new::<H>(
+                Some(entry.chain_hash.as_slice()),
+                &message,
+                signature.as_ref(),
+                42,
+            )
+            .unwrap();
+            assert_eq!(
+                hss_verify_audit_log::<H>(&[chained_entry], &[]),
+                Err(AuditLogError::BrokenChain { position: 0 })
+            );
+        }
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn detect_reused_and_unlogged_signatures() {
+        let mut audit_log = InMemoryAuditLog::default();
+        let (mut signing_key, _) =
+            hss_keygen::<H>(&parameters(), &gen_random_seed::<H>(), None).unwrap();
+
+        let mut signatures = Vec::new();
+        for timestamp in 0..2 {
+            signatures.push(
+                signing_key
+                    .try_sign_with_audit_log(&[timestamp as u8], timestamp, &mut audit_log)
+                    .unwrap(),
+            );
+        }
+        let mut snapshot = signing_key.duplicate();
+        signatures.push(
+            signing_key
+                .try_sign_with_audit_log(&[2], 2, &mut audit_log)
+                .unwrap(),
+        );
+
+        let signature_slices = signatures
+            .iter()
+            .map(|signature| signature.as_ref())
+            .collect::<Vec<_>>();
+        assert!(hss_verify_audit_log::<H>(&audit_log.entries, &signature_slices).is_ok());
+
+        let mut tampered_entries = audit_log.entries.clone();
+        tampered_entries[1].timestamp += 1;
+        assert_eq!(
+            hss_verify_audit_log::<H>(&tampered_entries, &[]),
+            Err(AuditLogError::BrokenChain { position: 1 })
+        );
+
+        // A restored snapshot reuses the index of the last signature
+        let reused_signature = snapshot.try_sign(&[3]).unwrap();
+        let mut reused_signatures = signature_slices.clone();
+        reused_signatures.push(reused_signature.as_ref());
+        assert_eq!(
+            hss_verify_audit_log::<H>(&audit_log.entries, &reused_signatures),
+            Err(AuditLogError::DuplicateIndex { index: 2 })
+        );
+
+        let mut reused_log = audit_log.clone();
+        let mut snapshot = signing_key.duplicate();
+        snapshot
+            .try_sign_with_audit_log(&[3], 3, &mut reused_log)
+            .unwrap();
+        signing_key
+            .try_sign_with_audit_log(&[4], 4, &mut reused_log)
+            .unwrap();
+        assert_eq!(
+            hss_verify_audit_log::<H>(&reused_log.entries, &[]),
+            Err(AuditLogError::DuplicateIndex { index: 3 })
+        );
+
+        let unlogged_signature = signing_key.try_sign(&[5]).unwrap();
+        assert_eq!(
+            hss_verify_audit_log::<H>(&audit_log.entries, &[unlogged_signature.as_ref()]),
+            Err(AuditLogError::UnloggedSignature { index: 4 })
+        );
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn file_audit_log() {
+        let directory = tempfile::tempdir().unwrap();
+        let mut audit_log = FileAuditLog::new(directory.path().join("audit.log"));
+        assert!(audit_log.entries().unwrap().is_empty());
+
+        let (mut signing_key, _) =
+            hss_keygen::<H>(&parameters(), &gen_random_seed::<H>(), None).unwrap();
+        let mut signatures = Vec::new();
+        for timestamp in 0..3 {
+            signatures.push(
+                signing_key
+                    .try_sign_with_audit_log(&[timestamp as u8], timestamp, &mut audit_log)
+                    .unwrap(),
+            );
+        }
+
+        let entries = audit_log.entries().unwrap();
+        assert_eq!(entries.len(), 3);
+        assert_eq!(
+            audit_log.last_chain_hash().unwrap(),
+            Some(entries[2].chain_hash)
+        );
+        // A new instance reads the chain hash of the last entry from the file
+        assert_eq!(
+            FileAuditLog::new(directory.path().join("audit.log"))
+                .last_chain_hash()
+                .unwrap(),
+            Some(entries[2].chain_hash)
+        );
+        assert_eq!(entries[2].timestamp, 2);
+        let signatures = signatures
+            .iter()
+            .map(|signature| signature.as_ref())
+            .collect::<Vec<_>>();
+        assert!(hss_verify_audit_log::<H>(&entries, &signatures).is_ok());
+    }
+}
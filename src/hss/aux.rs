@@ -1,27 +1,29 @@
 use core::convert::TryInto;
+use signature::Error;
 use subtle::ConstantTimeEq;
 use tinyvec::ArrayVec;
 
 use crate::{
     constants::{
-        DAUX_D, DAUX_PREFIX_LEN, D_DAUX, MAX_HASH_BLOCK_SIZE, MAX_HASH_SIZE, MAX_TREE_HEIGHT,
-        MIN_SUBTREE,
+        LmsTreeIdentifier, DAUX_D, DAUX_PREFIX_LEN, D_DAUX, ILEN, MAX_ALLOWED_HSS_LEVELS,
+        MAX_HASH_BLOCK_SIZE, MAX_HASH_SIZE, MAX_TREE_HEIGHT, MIN_SUBTREE,
     },
     hasher::HashChain,
     lms::parameters::LmsParameter,
     util::helper::read_and_advance,
+    HssParameter,
 };
 
 /**
 The implementation of aux data is mainly copied from the reference implementation in C (https://github.com/cisco/hash-sigs)
 For comments see the original source code.
 */
-
 type AuxLevel = u32;
 
 const AUX_DATA_MARKER: usize = 0;
 const NO_AUX_DATA: u8 = 0x00;
 const AUX_DATA_HASHES: usize = 4;
+const AUX_LEVEL_USED: AuxLevel = 0x80000000;
 
 const IPAD: u8 = 0x36;
 const OPAD: u8 = 0x5c;
@@ -31,6 +33,10 @@ pub struct MutableExpandedAuxData<'a> {
     pub data: [Option<&'a mut [u8]>; MAX_TREE_HEIGHT + 1],
     pub level: u32,
     pub hmac: &'a mut [u8],
+    // Only set for the trees of lower HSS levels, as these are replaced over time
+    pub lms_tree_identifier: Option<LmsTreeIdentifier>,
+    // Aux data of the active tree of the next lower HSS level
+    pub child_aux_data: &'a mut [u8],
 }
 
 pub fn hss_optimal_aux_level<H: HashChain>(
@@ -57,7 +63,7 @@ pub fn hss_optimal_aux_level<H: HashChain>(
 
         if max_length >= len_this_level {
             max_length -= len_this_level;
-            aux_level |= 0x80000000 | (1 << level);
+            aux_level |= AUX_LEVEL_USED | (1 << level);
         }
     }
 
@@ -68,27 +74,429 @@ pub fn hss_optimal_aux_level<H: HashChain>(
     aux_level
 }
 
+/**
+ * Layout of the aux data of a key, which caches levels of LMS trees to speed up signing.
+ *
+ * The levels are numbered by their depth, i.e. level `l` stores the `2^l` nodes of that depth and
+ * the level of the tree height stores the leafs. Bytes left by the top tree are used for the
+ * currently active trees of the lower HSS levels created with
+ * [`crate::HssParameter::with_aux_data_cache`], which are refreshed whenever such a tree is
+ * replaced. Returned by [`crate::aux_data_info`], [`crate::aux_data_info_for_budget`] and
+ * [`crate::aux_data_info_for_level`].
+ */
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct AuxDataInfo {
+    size: usize,
+    aux_level: AuxLevel,
+    tree_height: u8,
+    child_aux_levels: [AuxLevel; MAX_ALLOWED_HSS_LEVELS - 1],
+    child_tree_heights: [u8; MAX_ALLOWED_HSS_LEVELS - 1],
+}
+
+impl AuxDataInfo {
+    fn new<H: HashChain>(aux_level: AuxLevel, lms_parameter: LmsParameter<H>) -> Self {
+        Self {
+            size: aux_data_len::<H>(aux_level, 0),
+            aux_level,
+            tree_height: lms_parameter.get_tree_height(),
+            child_aux_levels: [0; MAX_ALLOWED_HSS_LEVELS - 1],
+            child_tree_heights: [0; MAX_ALLOWED_HSS_LEVELS - 1],
+        }
+    }
+
+    /// Aux data of no size, e.g. for an empty parameter set
+    fn empty() -> Self {
+        Self {
+            size: 0,
+            aux_level: 0,
+            tree_height: 0,
+            child_aux_levels: [0; MAX_ALLOWED_HSS_LEVELS - 1],
+            child_tree_heights: [0; MAX_ALLOWED_HSS_LEVELS - 1],
+        }
+    }
+
+    fn push_child<H: HashChain>(&mut self, level: usize, aux_level: AuxLevel, tree_height: u8) {
+        self.size += aux_data_len::<H>(aux_level, ILEN);
+        self.child_aux_levels[level - 1] = aux_level;
+        self.child_tree_heights[level - 1] = tree_height;
+    }
+
+    /// Bytes of aux data, which are kept by [`crate::keygen`] from a larger buffer
+    pub fn size(&self) -> usize {
+        self.size
+    }
+
+    /// Cached levels of the top tree from the root to the leafs
+    pub fn levels(&self) -> impl Iterator<Item = u8> + '_ {
+        cached_levels(self.aux_level, self.tree_height)
+    }
+
+    /// Cached levels of the active tree of the lower HSS level `hss_level` (starting with 1)
+    pub fn lower_tree_levels(&self, hss_level: usize) -> impl Iterator<Item = u8> + '_ {
+        let (aux_level, tree_height) = match hss_level.checked_sub(1) {
+            Some(index) if index < self.child_aux_levels.len() => {
+                (self.child_aux_levels[index], self.child_tree_heights[index])
+            }
+            _ => (0, 0),
+        };
+        cached_levels(aux_level, tree_height)
+    }
+
+    /// LM-OTS keys of the top tree generated per signature, including the one of the signing leaf
+    pub fn leaf_generations(&self) -> u64 {
+        let deepest_level = self.levels().last().unwrap_or(0);
+        1 << (self.tree_height - deepest_level)
+    }
+
+    /// Factor by which the aux data reduces the LM-OTS key generations of the top tree
+    pub fn sign_speedup(&self) -> u64 {
+        (1 << self.tree_height) / self.leaf_generations()
+    }
+}
+
+fn cached_levels(aux_level: AuxLevel, tree_height: u8) -> impl Iterator<Item = u8> {
+    (1..=tree_height).filter(move |level| aux_level & (1 << level) != 0)
+}
+
+/// Length of the aux data of a single tree, `prefix_len` bytes follow the level marker
+fn aux_data_len<H: HashChain>(aux_level: AuxLevel, prefix_len: usize) -> usize {
+    if aux_level == 0 {
+        return 1;
+    }
+
+    let size_hash = H::OUTPUT_SIZE as usize;
+    AUX_DATA_HASHES
+        + prefix_len
+        + size_hash
+        + (0..=MAX_TREE_HEIGHT)
+            .filter(|level| aux_level & (1 << level) != 0)
+            .map(|level| size_hash << level)
+            .sum::<usize>()
+}
+
+/**
+ * Describe the aux data, which [`crate::keygen`] creates in a buffer of `max_length` bytes.
+ * Without `parameters`, the aux data has no size.
+ */
+pub fn hss_aux_data_info_for_budget<H: HashChain>(
+    parameters: &[HssParameter<H>],
+    max_length: usize,
+) -> AuxDataInfo {
+    let lms_parameter = match parameters.first() {
+        Some(parameter) => *parameter.get_lms_parameter(),
+        None => return AuxDataInfo::empty(),
+    };
+    let mut info = AuxDataInfo::new(
+        hss_optimal_aux_level(max_length, lms_parameter, None),
+        lms_parameter,
+    );
+
+    if info.aux_level == 0 {
+        return info;
+    }
+
+    // The remaining bytes are handed to the lower levels, starting below the top tree
+    for (level, parameter) in parameters.iter().enumerate().skip(1) {
+        if !parameter.is_cached_in_aux_data() {
+            break;
+        }
+
+        let lms_parameter = *parameter.get_lms_parameter();
+        let max_length = match (max_length - info.size).checked_sub(ILEN) {
+            Some(max_length) => max_length,
+            None => break,
+        };
+
+        let aux_level = hss_optimal_aux_level(max_length, lms_parameter, None);
+        if aux_level == 0 {
+            break;
+        }
+        info.push_child::<H>(level, aux_level, lms_parameter.get_tree_height());
+    }
+
+    info
+}
+
+/**
+ * Describe the smallest aux data, which caches the top tree down to `level` (see
+ * [`AuxDataInfo`]). [`AuxDataInfo::size`] is the buffer size to pass to [`crate::keygen`].
+ * Without `parameters`, the aux data has no size.
+ */
+pub fn hss_aux_data_info_for_level<H: HashChain>(
+    parameters: &[HssParameter<H>],
+    level: u8,
+) -> AuxDataInfo {
+    let lms_parameter = match parameters.first() {
+        Some(parameter) => *parameter.get_lms_parameter(),
+        None => return AuxDataInfo::empty(),
+    };
+    let size_hash = lms_parameter.get_hash_function_output_size();
+
+    // Levels are cached from the leafs upwards in steps of MIN_SUBTREE
+    let max_length = AUX_DATA_HASHES
+        + size_hash
+        + (1..=lms_parameter.get_tree_height())
+            .rev()
+            .step_by(MIN_SUBTREE)
+            .filter(|&cached_level| cached_level <= level)
+            .map(|cached_level| size_hash << cached_level)
+            .sum::<usize>();
+
+    hss_aux_data_info_for_budget(parameters, max_length)
+}
+
+/**
+ * Describe existing aux data of a key with the given `parameters`. Fails if the aux data is
+ * malformed or `parameters` is empty. The aux data is not authenticated though, as this requires
+ * the signing key.
+ */
+pub fn hss_aux_data_info<H: HashChain>(
+    parameters: &[HssParameter<H>],
+    aux_data: &[u8],
+) -> Result<AuxDataInfo, Error> {
+    let lms_parameter = *parameters
+        .first()
+        .ok_or_else(Error::new)?
+        .get_lms_parameter();
+
+    if aux_data.is_empty() {
+        return Err(Error::new());
+    }
+    if !hss_is_aux_data_used(aux_data) {
+        return Ok(AuxDataInfo::new(0, lms_parameter));
+    }
+
+    let mut info = AuxDataInfo::new(read_aux_level(aux_data, lms_parameter)?, lms_parameter);
+
+    // Bytes following the top tree are only taken as lower levels if they are laid out like them,
+    // as aux data of the reference implementation may be followed by anything
+    for (level, parameter) in parameters.iter().enumerate().skip(1) {
+        let child_aux_data = &aux_data[info.size.min(aux_data.len())..];
+        if child_aux_data.is_empty() || !hss_is_aux_data_used(child_aux_data) {
+            break;
+        }
+
+        let lms_parameter = *parameter.get_lms_parameter();
+        match read_aux_level(child_aux_data, lms_parameter) {
+            Ok(aux_level) if aux_data_len::<H>(aux_level, ILEN) <= child_aux_data.len() => {
+                info.push_child::<H>(level, aux_level, lms_parameter.get_tree_height())
+            }
+            _ => break,
+        }
+    }
+
+    if aux_data.len() < info.size() {
+        return Err(Error::new());
+    }
+    Ok(info)
+}
+
+/**
+ * Reason why aux data was rejected by [`crate::SigningKey::validate_aux`].
+ */
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum AuxDataError {
+    /// The signing key could not be parsed
+    InvalidSigningKey,
+    /// The aux data does not cache any tree, e.g. it was never filled by [`crate::keygen`]
+    Unused,
+    /// The aux data does not match the layout for the parameters of the signing key
+    Malformed,
+    /// The MAC of the top tree does not match, i.e. the aux data is corrupted or belongs to
+    /// another key
+    NotAuthentic,
+}
+
+impl core::fmt::Display for AuxDataError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.write_str(match self {
+            AuxDataError::InvalidSigningKey => "invalid signing key",
+            AuxDataError::Unused => "aux data is unused",
+            AuxDataError::Malformed => "aux data is malformed",
+            AuxDataError::NotAuthentic => "aux data is not authentic",
+        })
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for AuxDataError {}
+
+impl From<AuxDataError> for Error {
+    fn from(_: AuxDataError) -> Self {
+        Error::new()
+    }
+}
+
+/**
+ * Check the layout and the MAC of the top tree of aux data, which was created for a key with the
+ * given `parameters` and `seed`. The sections of the lower HSS levels are not checked, as these
+ * are refreshed while signing anyway.
+ */
+pub fn hss_validate_aux_data<H: HashChain>(
+    parameters: &[HssParameter<H>],
+    aux_data: &[u8],
+    seed: &[u8],
+) -> Result<AuxDataInfo, AuxDataError> {
+    if aux_data.is_empty() || !hss_is_aux_data_used(aux_data) {
+        return Err(AuxDataError::Unused);
+    }
+
+    let info = hss_aux_data_info(parameters, aux_data).map_err(|_| AuxDataError::Malformed)?;
+
+    let key = compute_seed_derive::<H>(seed);
+    if !is_aux_data_authentic::<H>(aux_data, &key, 0) {
+        return Err(AuxDataError::NotAuthentic);
+    }
+    Ok(info)
+}
+
+fn read_aux_level<H: HashChain>(
+    aux_data: &[u8],
+    lms_parameter: LmsParameter<H>,
+) -> Result<AuxLevel, Error> {
+    let aux_level = AuxLevel::from_be_bytes(
+        aux_data
+            .get(..AUX_DATA_HASHES)
+            .ok_or_else(Error::new)?
+            .try_into()
+            .unwrap(),
+    );
+    let tree_height: u32 = lms_parameter.get_tree_height().into();
+    let level_mask = (1 << (tree_height + 1)) - 2;
+
+    if aux_level & AUX_LEVEL_USED == 0 || aux_level & !(AUX_LEVEL_USED | level_mask) != 0 {
+        return Err(Error::new());
+    }
+    Ok(aux_level)
+}
+
+/**
+ * Writes the level markers of the layout described by `info` into fresh aux data. The sections of
+ * the lower levels are authenticated for no tree yet, so that signing only ever rewrites sections
+ * created here and leaves any other bytes following the top tree untouched.
+ */
+pub fn hss_store_aux_layout<H: HashChain>(aux_data: &mut [u8], info: &AuxDataInfo, seed: &[u8]) {
+    hss_store_aux_marker(aux_data, info.aux_level);
+
+    let mut index = aux_data_len::<H>(info.aux_level, 0);
+    for &aux_level in info
+        .child_aux_levels
+        .iter()
+        .take_while(|&&level| level != 0)
+    {
+        let len = aux_data_len::<H>(aux_level, ILEN);
+        let child_aux_data = &mut aux_data[index..index + len];
+        child_aux_data.fill(0);
+        hss_store_aux_marker(child_aux_data, aux_level);
+
+        if let Some(mut expanded_aux_data) = split_aux_data::<H>(child_aux_data, ILEN) {
+            expanded_aux_data.lms_tree_identifier = Some(LmsTreeIdentifier::default());
+            hss_finalize_aux_data::<H>(&mut expanded_aux_data, seed);
+        }
+        index += len;
+    }
+}
+
 pub fn hss_expand_aux_data<'a, H: HashChain>(
     aux_data: Option<&'a mut [u8]>,
     seed: Option<&'a [u8]>,
 ) -> Option<MutableExpandedAuxData<'a>> {
-    let mut index = 0;
+    let aux_data = aux_data.unwrap();
+
+    if aux_data[AUX_DATA_MARKER] == NO_AUX_DATA {
+        return None;
+    }
+
+    // Check if data is valid
+    if let Some(seed) = seed {
+        let key = compute_seed_derive::<H>(seed);
+        if !is_aux_data_authentic::<H>(aux_data, &key, 0) {
+            return None;
+        }
+    }
 
-    let mut expanded_aux_data: MutableExpandedAuxData = Default::default();
+    split_aux_data::<H>(aux_data, 0)
+}
 
-    let mut aux_data = aux_data.unwrap();
+/**
+ * Expand the aux data of the active tree of a lower HSS level, which follows the aux data of its
+ * parent. Bytes that are not authentic aux data of a lower level are left untouched. If the aux
+ * data belongs to a replaced tree, it is cleared for the tree `lms_tree_identifier`, which is
+ * signalled by `false` together with the expanded aux data. The aux data then has to be finalized
+ * after the tree has been generated.
+ */
+pub fn hss_expand_child_aux_data<'a, H: HashChain>(
+    aux_data: &'a mut [u8],
+    seed: &[u8],
+    lms_tree_identifier: &LmsTreeIdentifier,
+) -> Option<(MutableExpandedAuxData<'a>, bool)> {
+    if aux_data.len() < AUX_DATA_HASHES + ILEN || aux_data[AUX_DATA_MARKER] == NO_AUX_DATA {
+        return None;
+    }
 
-    if aux_data[AUX_DATA_MARKER] == NO_AUX_DATA {
+    let key = compute_seed_derive::<H>(seed);
+    if !is_aux_data_authentic::<H>(aux_data, &key, ILEN) {
         return None;
     }
 
+    let identifier = AUX_DATA_HASHES..AUX_DATA_HASHES + ILEN;
+    let is_valid = aux_data[identifier.clone()] == lms_tree_identifier[..];
+    aux_data[identifier].copy_from_slice(lms_tree_identifier);
+
+    let mut expanded_aux_data = split_aux_data::<H>(aux_data, ILEN)?;
+
+    if !is_valid {
+        for data in expanded_aux_data.data.iter_mut().flatten() {
+            data.fill(0);
+        }
+    }
+    expanded_aux_data.lms_tree_identifier = Some(*lms_tree_identifier);
+
+    Some((expanded_aux_data, is_valid))
+}
+
+/// Length of the level marker, `prefix_len` following bytes and the cached levels
+fn aux_data_nodes_len<H: HashChain>(aux_data: &[u8], prefix_len: usize) -> Option<usize> {
+    let aux_level = AuxLevel::from_be_bytes(aux_data.get(..AUX_DATA_HASHES)?.try_into().unwrap());
+
+    let len = aux_data_len::<H>(aux_level, prefix_len) - H::OUTPUT_SIZE as usize;
+    if aux_data.len() < len + H::OUTPUT_SIZE as usize {
+        return None;
+    }
+    Some(len)
+}
+
+fn is_aux_data_authentic<H: HashChain>(aux_data: &[u8], key: &[u8], prefix_len: usize) -> bool {
+    let len_aux_data = match aux_data_nodes_len::<H>(aux_data, prefix_len) {
+        Some(len_aux_data) => len_aux_data,
+        None => return false,
+    };
+    let (aux_data, aux_data_mac) = aux_data.split_at(len_aux_data);
+
+    bool::from(compute_hmac::<H>(key, aux_data).ct_eq(&aux_data_mac[..H::OUTPUT_SIZE as usize]))
+}
+
+fn split_aux_data<H: HashChain>(
+    aux_data: &mut [u8],
+    prefix_len: usize,
+) -> Option<MutableExpandedAuxData<'_>> {
+    aux_data_nodes_len::<H>(aux_data, prefix_len)?;
+
+    let mut index = 0;
+
     // REMARK: Reference implementation treats that as u64 and ANDs it with 0x7ffffffffL after its stored in expanded_aux_data
     // However in our opinion that should make no difference, because we only read 4 bytes.
-    expanded_aux_data.level = u32::from_be_bytes(
+    let level = u32::from_be_bytes(
         read_and_advance(aux_data, 4, &mut index)
             .try_into()
             .unwrap(),
     );
+    index += prefix_len;
+
+    let mut expanded_aux_data = MutableExpandedAuxData {
+        level,
+        ..Default::default()
+    };
 
     const LEN_LAYER_SIZES: usize = 1 + MAX_TREE_HEIGHT;
     let mut layer_sizes: ArrayVec<[usize; LEN_LAYER_SIZES]> =
@@ -100,18 +508,7 @@ pub fn hss_expand_aux_data<'a, H: HashChain>(
         layer_sizes[index] = (H::OUTPUT_SIZE as usize) << index;
     }
 
-    // Check if data is valid
-    if let Some(seed) = seed {
-        let len_aux_data = index + layer_sizes.iter().sum::<usize>();
-        let (aux_data, aux_data_mac) = aux_data.split_at(len_aux_data);
-
-        let key = compute_seed_derive::<H>(seed);
-        if !bool::from(compute_hmac::<H>(&key, aux_data).ct_eq(aux_data_mac)) {
-            return None;
-        }
-    }
-
-    aux_data = &mut aux_data[index..];
+    let mut aux_data = &mut aux_data[index..];
 
     for (index, layer_size) in layer_sizes
         .iter()
@@ -123,24 +520,13 @@ pub fn hss_expand_aux_data<'a, H: HashChain>(
         expanded_aux_data.data[index] = Some(data);
         aux_data = data_rest;
     }
-    expanded_aux_data.hmac = aux_data;
+    let (hmac, child_aux_data) = aux_data.split_at_mut(H::OUTPUT_SIZE as usize);
+    expanded_aux_data.hmac = hmac;
+    expanded_aux_data.child_aux_data = child_aux_data;
 
     Some(expanded_aux_data)
 }
 
-pub fn hss_get_aux_data_len<H: HashChain>(
-    max_length: usize,
-    lms_parameter: LmsParameter<H>,
-) -> usize {
-    let mut len = 0;
-
-    if hss_optimal_aux_level(max_length, lms_parameter, Some(&mut len)) == 0 {
-        return 1;
-    }
-
-    len
-}
-
 pub fn hss_store_aux_marker(aux_data: &mut [u8], aux_level: AuxLevel) {
     if aux_level == 0 {
         aux_data[AUX_DATA_MARKER] = NO_AUX_DATA;
@@ -177,6 +563,9 @@ pub fn hss_finalize_aux_data<H: HashChain>(data: &mut MutableExpandedAuxData, se
     let aux_seed = compute_seed_derive::<H>(seed);
 
     let mut hasher = compute_hmac_ipad::<H>(&aux_seed).chain(data.level.to_be_bytes());
+    if let Some(lms_tree_identifier) = data.lms_tree_identifier {
+        hasher.update(&lms_tree_identifier);
+    }
 
     for i in 0..MAX_TREE_HEIGHT {
         if let Some(x) = data.data[i].as_mut() {
@@ -266,7 +655,13 @@ mod tests {
     use crate::util::helper::test_helper::gen_random_seed;
     use crate::{
         constants::MAX_HASH_SIZE,
-        hss::{aux::hss_expand_aux_data, hss_keygen},
+        hss::{
+            aux::{
+                hss_aux_data_info, hss_aux_data_info_for_budget, hss_aux_data_info_for_level,
+                hss_expand_aux_data,
+            },
+            hss_keygen,
+        },
         HssParameter, LmotsAlgorithm, LmsAlgorithm,
     };
 
@@ -291,4 +686,49 @@ mod tests {
         hss_expand_aux_data::<H>(Some(aux_slice), Some(seed.as_slice()))
             .expect("expand_aux_data should return None!");
     }
+
+    #[test]
+    fn aux_data_sizing() {
+        type H = Sha256_256;
+        let seed = gen_random_seed::<H>();
+
+        let lmots = LmotsAlgorithm::LmotsW2;
+        let lms = LmsAlgorithm::LmsH5;
+        let parameters = [HssParameter::new(lmots, lms), HssParameter::new(lmots, lms)];
+
+        // Levels 1 and 3 of the top tree, the marker and the MAC
+        let info = hss_aux_data_info_for_level(&parameters, 4);
+        assert_eq!(info.size(), 4 + MAX_HASH_SIZE * (1 + 2 + 8));
+        assert!(info.levels().eq([1, 3]));
+        assert_eq!(info.leaf_generations(), 4);
+        assert_eq!(info.sign_speedup(), 8);
+        assert_eq!(hss_aux_data_info_for_budget(&parameters, info.size()), info);
+        // Without room for level 1, only level 3 is cached
+        assert!(hss_aux_data_info_for_budget(&parameters, info.size() - 1)
+            .levels()
+            .eq([3]));
+
+        let mut aux_data = [0u8; 1_000];
+        let aux_slice: &mut &mut [u8] = &mut &mut aux_data[..info.size()];
+        let _ =
+            hss_keygen::<H>(&parameters, &seed, Some(aux_slice)).expect("Should generate HSS keys");
+        assert_eq!(aux_slice.len(), info.size());
+        assert_eq!(hss_aux_data_info(&parameters, aux_slice).unwrap(), info);
+        assert!(hss_aux_data_info(&parameters, &aux_slice[..info.size() - 1]).is_err());
+
+        let no_aux_data = hss_aux_data_info_for_budget(&parameters, 10);
+        assert_eq!(no_aux_data.size(), 1);
+        assert_eq!(no_aux_data.levels().count(), 0);
+        assert_eq!(no_aux_data.sign_speedup(), 1);
+        assert_eq!(hss_aux_data_info(&parameters, &[0u8]).unwrap(), no_aux_data);
+
+        // An empty parameter set has no aux data
+        let empty_parameters: [HssParameter<H>; 0] = [];
+        let empty = hss_aux_data_info_for_budget(&empty_parameters, 1_000);
+        assert_eq!(empty.size(), 0);
+        assert_eq!(empty.levels().count(), 0);
+        assert_eq!(empty.sign_speedup(), 1);
+        assert_eq!(hss_aux_data_info_for_level(&empty_parameters, 4), empty);
+        assert!(hss_aux_data_info(&empty_parameters, aux_slice).is_err());
+    }
 }
@@ -4,26 +4,27 @@ use tinyvec::ArrayVec;
 
 use crate::{
     constants::{MAX_ALLOWED_HSS_LEVELS, MAX_HSS_PUBLIC_KEY_LENGTH},
-    hasher::HashChain,
+    hasher::{HashChain, HashOperations},
     hss::aux::{
-        hss_expand_aux_data, hss_finalize_aux_data, hss_optimal_aux_level, hss_store_aux_marker,
+        hss_aux_data_info_for_budget, hss_expand_aux_data, hss_expand_child_aux_data,
+        hss_finalize_aux_data, hss_store_aux_layout,
     },
     lms::{
         self,
         definitions::{InMemoryLmsPublicKey, LmsPrivateKey, LmsPublicKey},
         generate_key_pair,
-        parameters::LmsParameter,
     },
     util::helper::read_and_advance,
 };
-use crate::{hss::aux::hss_get_aux_data_len, lms::signing::LmsSignature};
+use crate::{lms::signing::LmsSignature, HssParameter};
 
 use super::{
     aux::{hss_is_aux_data_used, MutableExpandedAuxData},
     reference_impl_private_key::{
         generate_child_seed_and_lms_tree_identifier, generate_signature_randomizer,
-        ReferenceImplPrivateKey,
+        ReferenceImplPrivateKey, SeedAndLmsTreeIdentifier,
     },
+    signing::HssSignedPublicKey,
 };
 
 #[derive(Debug, Default, PartialEq)]
@@ -31,6 +32,8 @@ pub struct HssPrivateKey<H: HashChain> {
     pub private_key: ArrayVec<[LmsPrivateKey<H>; MAX_ALLOWED_HSS_LEVELS]>,
     pub public_key: ArrayVec<[LmsPublicKey<H>; MAX_ALLOWED_HSS_LEVELS - 1]>,
     pub signatures: ArrayVec<[LmsSignature<H>; MAX_ALLOWED_HSS_LEVELS - 1]>, // Only L - 1 signatures needed
+    // Signed public key of the top tree of a delegated key, which got signed by its parent
+    pub delegation: Option<HssSignedPublicKey<H>>,
 }
 
 impl<H: HashChain> HssPrivateKey<H> {
@@ -41,13 +44,36 @@ impl<H: HashChain> HssPrivateKey<H> {
     pub fn from(
         private_key: &ReferenceImplPrivateKey<H>,
         aux_data: &mut Option<MutableExpandedAuxData>,
+        hash_operations: &mut HashOperations,
     ) -> Result<Self, ()> {
-        let mut hss_private_key: HssPrivateKey<H> = Default::default();
-
-        let mut current_seed = private_key.generate_root_seed_and_lms_tree_identifier();
+        let current_seed = private_key.generate_root_seed_and_lms_tree_identifier(hash_operations);
         let parameters = private_key.compressed_parameter.to::<H>()?;
         let used_leafs_indexes = private_key.compressed_used_leafs_indexes.to(&parameters);
 
+        Self::from_seed(
+            current_seed,
+            &parameters,
+            &used_leafs_indexes,
+            private_key.seed.as_slice(),
+            aux_data,
+            hash_operations,
+        )
+    }
+
+    /**
+     * Expand the trees of `parameters`, starting with the top tree derived from `current_seed`.
+     * `aux_seed` authenticates the aux data.
+     */
+    pub fn from_seed(
+        mut current_seed: SeedAndLmsTreeIdentifier<H>,
+        parameters: &[HssParameter<H>],
+        used_leafs_indexes: &[u32],
+        aux_seed: &[u8],
+        aux_data: &mut Option<MutableExpandedAuxData>,
+        hash_operations: &mut HashOperations,
+    ) -> Result<Self, ()> {
+        let mut hss_private_key: HssPrivateKey<H> = Default::default();
+
         let lms_private_key = LmsPrivateKey {
             seed: current_seed.seed.clone(),
             lms_tree_identifier: current_seed.lms_tree_identifier,
@@ -63,20 +89,62 @@ impl<H: HashChain> HssPrivateKey<H> {
             current_seed = generate_child_seed_and_lms_tree_identifier::<H>(
                 &current_seed,
                 &parent_used_leafs_index,
+                hash_operations,
+            );
+            let signature_randomizer = generate_signature_randomizer::<H>(
+                &current_seed,
+                &parent_used_leafs_index,
+                hash_operations,
             );
-            let signature_randomizer =
-                generate_signature_randomizer::<H>(&current_seed, &parent_used_leafs_index);
 
-            let lms_keypair =
-                generate_key_pair(&current_seed, parameter, &used_leafs_indexes[i], &mut None);
+            let (mut child_aux_data, is_child_aux_data_valid) = aux_data
+                .as_mut()
+                .and_then(|aux_data| {
+                    hss_expand_child_aux_data::<H>(
+                        core::mem::take(&mut aux_data.child_aux_data),
+                        aux_seed,
+                        &current_seed.lms_tree_identifier,
+                    )
+                })
+                .map_or((None, true), |(child_aux_data, is_valid)| {
+                    (Some(child_aux_data), is_valid)
+                });
+
+            let lms_keypair = generate_key_pair(
+                &current_seed,
+                parameter,
+                &used_leafs_indexes[i],
+                &mut child_aux_data,
+                hash_operations,
+            );
+
+            // The aux data of a replaced tree got regenerated together with the public key
+            if let Some(child_aux_data) = child_aux_data.as_mut() {
+                if !is_child_aux_data_valid {
+                    hss_finalize_aux_data::<H>(child_aux_data, aux_seed);
+                }
+            }
 
-            let signature = lms::signing::LmsSignature::sign(
-                &mut hss_private_key.private_key[i - 1],
-                lms_keypair.public_key.to_binary_representation().as_slice(),
-                &signature_randomizer,
-                aux_data,
-            )?;
-            *aux_data = None;
+            let public_key = lms_keypair.public_key.to_binary_representation();
+            let signature = if let Some(trials) = parameter.get_signed_public_key_trials() {
+                lms::signing::LmsSignature::sign_with_optimized_randomizer(
+                    &mut hss_private_key.private_key[i - 1],
+                    public_key.as_slice(),
+                    &signature_randomizer,
+                    trials,
+                    aux_data,
+                    hash_operations,
+                )?
+            } else {
+                lms::signing::LmsSignature::sign(
+                    &mut hss_private_key.private_key[i - 1],
+                    public_key.as_slice(),
+                    &signature_randomizer,
+                    aux_data,
+                    hash_operations,
+                )?
+            };
+            *aux_data = child_aux_data;
 
             hss_private_key.private_key.push(lms_keypair.private_key);
             hss_private_key.public_key.push(lms_keypair.public_key);
@@ -86,25 +154,28 @@ impl<H: HashChain> HssPrivateKey<H> {
         Ok(hss_private_key)
     }
 
+    /**
+     * Expand the aux data of the trees of `parameters`, which is authenticated with `aux_seed`.
+     * Unused aux data is laid out for `parameters`, but its trees still have to be filled.
+     */
     pub fn get_expanded_aux_data<'a>(
         aux_data: Option<&'a mut &mut [u8]>,
-        private_key: &'a ReferenceImplPrivateKey<H>,
-        top_lms_parameter: &LmsParameter<H>,
+        aux_seed: &'a [u8],
+        parameters: &[HssParameter<H>],
         is_aux_data_used: bool,
     ) -> Option<MutableExpandedAuxData<'a>> {
         let aux_data = aux_data?;
 
         if is_aux_data_used {
-            return hss_expand_aux_data::<H>(Some(aux_data), Some(private_key.seed.as_slice()));
+            return hss_expand_aux_data::<H>(Some(aux_data), Some(aux_seed));
         }
 
         // Shrink input slice
-        let aux_len = hss_get_aux_data_len(aux_data.len(), *top_lms_parameter);
+        let aux_data_info = hss_aux_data_info_for_budget(parameters, aux_data.len());
         let moved = core::mem::take(aux_data);
-        *aux_data = &mut moved[..aux_len];
+        *aux_data = &mut moved[..aux_data_info.size()];
 
-        let aux_level = hss_optimal_aux_level(aux_len, *top_lms_parameter, None);
-        hss_store_aux_marker(aux_data, aux_level);
+        hss_store_aux_layout::<H>(aux_data, &aux_data_info, aux_seed);
 
         hss_expand_aux_data::<H>(Some(aux_data), None)
     }
@@ -154,12 +225,24 @@ impl<H: HashChain> HssPublicKey<H> {
     pub fn from(
         private_key: &ReferenceImplPrivateKey<H>,
         aux_data: Option<&mut &mut [u8]>,
+        hash_operations: &mut HashOperations,
     ) -> Result<Self, ()> {
         let parameters = private_key.compressed_parameter.to::<H>()?;
-        let levels = parameters.len();
-        let used_leafs_indexes = private_key.compressed_used_leafs_indexes.to(&parameters);
+        Self::from_parameters(private_key, &parameters, aux_data, hash_operations)
+    }
 
-        let top_lms_parameter = parameters[0].get_lms_parameter();
+    /**
+     * Like [`HssPublicKey::from`], but lays out fresh aux data for the `parameters` the key was
+     * generated with, which also tell the lower levels to cache in the aux data.
+     */
+    pub fn from_parameters(
+        private_key: &ReferenceImplPrivateKey<H>,
+        parameters: &[HssParameter<H>],
+        aux_data: Option<&mut &mut [u8]>,
+        hash_operations: &mut HashOperations,
+    ) -> Result<Self, ()> {
+        let levels = parameters.len();
+        let used_leafs_indexes = private_key.compressed_used_leafs_indexes.to(parameters);
 
         let is_aux_data_used = if let Some(ref aux_data) = aux_data {
             hss_is_aux_data_used(aux_data)
@@ -169,18 +252,19 @@ impl<H: HashChain> HssPublicKey<H> {
 
         let mut expanded_aux_data = HssPrivateKey::get_expanded_aux_data(
             aux_data,
-            private_key,
-            top_lms_parameter,
+            private_key.seed.as_slice(),
+            parameters,
             is_aux_data_used,
         );
 
-        let current_seed = private_key.generate_root_seed_and_lms_tree_identifier();
+        let current_seed = private_key.generate_root_seed_and_lms_tree_identifier(hash_operations);
 
         let lms_keypair = generate_key_pair(
             &current_seed,
             &parameters[0],
             &used_leafs_indexes[0],
             &mut expanded_aux_data,
+            hash_operations,
         );
 
         if let Some(expanded_aux_data) = expanded_aux_data.as_mut() {
@@ -210,10 +294,7 @@ impl<'a, H: HashChain> InMemoryHssPublicKey<'a, H> {
 
         let level = u32::from_be_bytes(read_and_advance(data, 4, &mut index).try_into().unwrap());
 
-        let public_key = match InMemoryLmsPublicKey::new(&data[index..]) {
-            None => return None,
-            Some(x) => x,
-        };
+        let public_key = InMemoryLmsPublicKey::new(&data[index..])?;
 
         Some(Self {
             public_key,
@@ -228,6 +309,7 @@ mod tests {
 
     use crate::util::helper::test_helper::gen_random_seed;
     use crate::{
+        constants::SIGNED_PUBLIC_KEY_HASH_OPTIMIZATIONS,
         hasher::sha256::Sha256_256,
         hss::{
             definitions::InMemoryHssPublicKey,
@@ -315,13 +397,15 @@ mod tests {
         let seed = gen_random_seed::<H>();
         let mut rfc_key = ReferenceImplPrivateKey::generate(&parameters, &seed).unwrap();
 
-        let hss_key_before = HssPrivateKey::from(&rfc_key, &mut None).unwrap();
+        let hss_key_before =
+            HssPrivateKey::from(&rfc_key, &mut None, &mut Default::default()).unwrap();
 
         for _ in 0..increment_by {
             rfc_key.increment(&hss_key_before);
         }
 
-        let hss_key_after = HssPrivateKey::from(&rfc_key, &mut None).unwrap();
+        let hss_key_after =
+            HssPrivateKey::from(&rfc_key, &mut None, &mut Default::default()).unwrap();
 
         (hss_key_before, hss_key_after)
     }
@@ -340,7 +424,8 @@ mod tests {
 
         let seed = gen_random_seed::<H>();
         let mut private_key = ReferenceImplPrivateKey::generate(&parameters, &seed).unwrap();
-        let hss_key = HssPrivateKey::from(&private_key, &mut None).unwrap();
+        let hss_key =
+            HssPrivateKey::from(&private_key, &mut None, &mut Default::default()).unwrap();
 
         let tree_heights = hss_key
             .private_key
@@ -352,7 +437,8 @@ mod tests {
 
         const STEP_BY: usize = 27;
         for index in (0..total_ots_count).step_by(STEP_BY) {
-            let hss_key = HssPrivateKey::from(&private_key, &mut None).unwrap();
+            let hss_key =
+                HssPrivateKey::from(&private_key, &mut None, &mut Default::default()).unwrap();
 
             assert_eq!(hss_key.get_lifetime(), total_ots_count - index,);
 
@@ -376,11 +462,308 @@ mod tests {
         let seed = gen_random_seed::<H>();
         let private_key = ReferenceImplPrivateKey::generate(&parameters, &seed).unwrap();
 
-        let hss_key = HssPrivateKey::from(&private_key, &mut None).unwrap();
-        let hss_key_second = HssPrivateKey::from(&private_key, &mut None).unwrap();
+        let hss_key =
+            HssPrivateKey::from(&private_key, &mut None, &mut Default::default()).unwrap();
+        let hss_key_second =
+            HssPrivateKey::from(&private_key, &mut None, &mut Default::default()).unwrap();
         assert_eq!(hss_key, hss_key_second);
     }
 
+    #[test]
+    fn aux_data_of_lower_levels_follows_child_tree() {
+        use crate::hss::aux::{
+            hss_aux_data_info_for_budget, hss_expand_child_aux_data, hss_finalize_aux_data,
+        };
+
+        type H = Sha256_256;
+
+        let lmots = LmotsAlgorithm::LmotsW2;
+        let lms = LmsAlgorithm::LmsH5;
+        let parameters = [
+            HssParameter::<H>::new(lmots, lms),
+            HssParameter::<H>::new(lmots, lms).with_aux_data_cache(),
+        ];
+
+        let seed = gen_random_seed::<H>();
+        let mut private_key = ReferenceImplPrivateKey::generate(&parameters, &seed).unwrap();
+
+        let info = hss_aux_data_info_for_budget(&parameters, 10_000);
+        let top_len = hss_aux_data_info_for_budget(&parameters[..1], 10_000).size();
+        let child_aux = top_len..info.size();
+        let child_identifier = top_len + 4..top_len + 4 + 16;
+
+        let fresh_aux_data = |private_key: &ReferenceImplPrivateKey<H>| {
+            let mut aux_data = [0u8; 10_000];
+            HssPublicKey::from_parameters(
+                private_key,
+                &parameters,
+                Some(&mut &mut aux_data[..]),
+                &mut Default::default(),
+            )
+            .unwrap();
+            aux_data
+        };
+        let expand = |private_key: &ReferenceImplPrivateKey<H>, aux_data: &mut [u8]| {
+            let aux_slice = &mut &mut aux_data[..info.size()];
+            let mut expanded_aux_data = HssPrivateKey::get_expanded_aux_data(
+                Some(aux_slice),
+                private_key.seed.as_slice(),
+                &parameters,
+                true,
+            );
+            HssPrivateKey::from(private_key, &mut expanded_aux_data, &mut Default::default())
+                .unwrap()
+        };
+
+        // The first expansion fills the aux data of the child tree
+        let mut aux_data = fresh_aux_data(&private_key);
+        let unfilled_aux_data = aux_data;
+        let hss_key = expand(&private_key, &mut aux_data);
+        let reference_key =
+            HssPrivateKey::from(&private_key, &mut None, &mut Default::default()).unwrap();
+        assert_eq!(hss_key, reference_key);
+        assert_ne!(
+            aux_data[child_aux.clone()],
+            unfilled_aux_data[child_aux.clone()]
+        );
+        assert_eq!(
+            aux_data[child_identifier.clone()],
+            reference_key.private_key[1].lms_tree_identifier
+        );
+        assert_eq!(aux_data[..top_len], unfilled_aux_data[..top_len]);
+
+        // Later expansions read the child tree from the aux data without rewriting it
+        let filled_aux_data = aux_data;
+        assert_eq!(expand(&private_key, &mut aux_data), reference_key);
+        assert!(aux_data == filled_aux_data);
+
+        // A forged, but authentic node of the child tree shows up in its public key
+        let seed = private_key.seed.clone();
+        let lms_tree_identifier = reference_key.private_key[1].lms_tree_identifier;
+        let forge = |aux_data: &mut [u8]| {
+            let (mut child_aux_data, is_valid) = hss_expand_child_aux_data::<H>(
+                &mut aux_data[top_len..info.size()],
+                seed.as_slice(),
+                &lms_tree_identifier,
+            )
+            .unwrap();
+            assert!(is_valid);
+            child_aux_data.data[1].as_mut().unwrap()[0] ^= 1;
+            hss_finalize_aux_data::<H>(&mut child_aux_data, seed.as_slice());
+        };
+        forge(&mut aux_data);
+        assert_ne!(
+            expand(&private_key, &mut aux_data).public_key[0],
+            reference_key.public_key[0]
+        );
+        forge(&mut aux_data);
+        assert!(aux_data == filled_aux_data);
+
+        // The next child tree replaces the cached one, like in freshly generated aux data
+        for _ in 0..32 {
+            private_key.increment(&hss_key);
+        }
+        let hss_key = expand(&private_key, &mut aux_data);
+        assert_eq!(
+            hss_key,
+            HssPrivateKey::from(&private_key, &mut None, &mut Default::default()).unwrap()
+        );
+        assert_ne!(
+            aux_data[child_aux.clone()],
+            filled_aux_data[child_aux.clone()]
+        );
+        assert_eq!(
+            aux_data[child_identifier],
+            hss_key.private_key[1].lms_tree_identifier
+        );
+
+        let mut regenerated_aux_data = fresh_aux_data(&private_key);
+        expand(&private_key, &mut regenerated_aux_data);
+        assert_eq!(aux_data[child_aux.clone()], regenerated_aux_data[child_aux]);
+    }
+
+    #[test]
+    #[cfg(feature = "verbose")]
+    fn aux_data_of_lower_levels() {
+        use crate::{hasher::HashOperations, hss::aux::hss_aux_data_info};
+
+        type H = Sha256_256;
+
+        let lmots = LmotsAlgorithm::LmotsW2;
+        let lms = LmsAlgorithm::LmsH5;
+        let parameters = [
+            HssParameter::<H>::new(lmots, lms),
+            HssParameter::<H>::new(lmots, lms).with_aux_data_cache(),
+        ];
+
+        let seed = gen_random_seed::<H>();
+        let mut private_key = ReferenceImplPrivateKey::generate(&parameters, &seed).unwrap();
+
+        let mut aux_data = [0u8; 10_000];
+        let aux_slice: &mut &mut [u8] = &mut &mut aux_data[..];
+        HssPublicKey::from_parameters(
+            &private_key,
+            &parameters,
+            Some(aux_slice),
+            &mut Default::default(),
+        )
+        .unwrap();
+        let aux_data_info = hss_aux_data_info(&parameters, aux_slice).unwrap();
+        assert!(aux_data_info.lower_tree_levels(1).eq([1, 3, 5]));
+
+        let expand = |private_key: &ReferenceImplPrivateKey<H>, aux_data: &mut [u8]| {
+            let mut hash_operations = HashOperations::default();
+            let aux_slice = &mut &mut aux_data[..];
+            let mut expanded_aux_data = HssPrivateKey::get_expanded_aux_data(
+                Some(aux_slice),
+                private_key.seed.as_slice(),
+                &parameters,
+                true,
+            );
+            let hss_key =
+                HssPrivateKey::from(private_key, &mut expanded_aux_data, &mut hash_operations)
+                    .unwrap();
+            assert_eq!(
+                hss_key,
+                HssPrivateKey::from(private_key, &mut None, &mut Default::default()).unwrap()
+            );
+            (hss_key, hash_operations.chain)
+        };
+
+        // 32 leafs with 133 chains of 3 steps each, only the signature of the child public key
+        // remains once the child tree is cached
+        let tree_chains = 32 * 133 * 3;
+
+        // The aux data of the child tree is filled by the first expansion
+        let (hss_key, chains) = expand(&private_key, aux_slice);
+        assert!(chains > tree_chains);
+        assert!(expand(&private_key, aux_slice).1 < 133 * 3);
+
+        // The next child tree replaces the cached one
+        for _ in 0..32 {
+            private_key.increment(&hss_key);
+        }
+        assert!(expand(&private_key, aux_slice).1 > tree_chains);
+        assert!(expand(&private_key, aux_slice).1 < 133 * 3);
+    }
+
+    #[test]
+    fn fast_verify_signed_public_key() {
+        type H = Sha256_256;
+
+        let lmots = LmotsAlgorithm::LmotsW4;
+        let lms = LmsAlgorithm::LmsH2;
+        let seed = gen_random_seed::<H>();
+
+        let private_key = ReferenceImplPrivateKey::generate(
+            &[
+                HssParameter::<H>::new(lmots, lms),
+                HssParameter::<H>::new(lmots, lms),
+            ],
+            &seed,
+        )
+        .unwrap();
+        let fast_verify_private_key = ReferenceImplPrivateKey::generate(
+            &[
+                HssParameter::<H>::new(lmots, lms),
+                HssParameter::<H>::new(lmots, lms).with_fast_verify_signed_public_key(),
+            ],
+            &seed,
+        )
+        .unwrap();
+
+        // The choice must survive storing the key, as the signature would change otherwise
+        let stored_private_key = ReferenceImplPrivateKey::<H>::from_binary_representation(
+            fast_verify_private_key
+                .to_binary_representation()
+                .as_slice(),
+        )
+        .unwrap();
+        assert!(
+            stored_private_key.compressed_parameter.to::<H>().unwrap()[1]
+                .is_fast_verify_signed_public_key()
+        );
+
+        let hss_key =
+            HssPrivateKey::from(&private_key, &mut None, &mut Default::default()).unwrap();
+        let fast_verify_hss_key =
+            HssPrivateKey::from(&stored_private_key, &mut None, &mut Default::default()).unwrap();
+        assert_eq!(
+            fast_verify_hss_key,
+            HssPrivateKey::from(&fast_verify_private_key, &mut None, &mut Default::default())
+                .unwrap()
+        );
+
+        assert_eq!(hss_key.public_key, fast_verify_hss_key.public_key);
+        assert_ne!(hss_key.signatures, fast_verify_hss_key.signatures);
+
+        let lmots_signature = &fast_verify_hss_key.signatures[0].lmots_signature;
+        assert!(
+            lmots_signature.verify_chain_steps()
+                < lmots_signature.lmots_parameter.get_max_hash_iterations() / 2
+        );
+
+        assert!(ReferenceImplPrivateKey::generate(
+            &[HssParameter::<H>::new(lmots, lms).with_fast_verify_signed_public_key()],
+            &seed,
+        )
+        .is_err());
+
+        // The count of randomizers tried is stored in the key, so other counts don't change the
+        // signatures of existing keys
+        assert_eq!(
+            stored_private_key.compressed_parameter.to::<H>().unwrap()[1]
+                .get_signed_public_key_trials(),
+            Some(SIGNED_PUBLIC_KEY_HASH_OPTIMIZATIONS)
+        );
+        let more_trials_private_key = ReferenceImplPrivateKey::generate(
+            &[
+                HssParameter::<H>::new(lmots, lms),
+                HssParameter::<H>::new(lmots, lms).with_signed_public_key_trials(1 << 12),
+            ],
+            &seed,
+        )
+        .unwrap();
+        let stored_more_trials_private_key =
+            ReferenceImplPrivateKey::<H>::from_binary_representation(
+                more_trials_private_key
+                    .to_binary_representation()
+                    .as_slice(),
+            )
+            .unwrap();
+        assert_eq!(
+            stored_more_trials_private_key
+                .compressed_parameter
+                .to::<H>()
+                .unwrap()[1]
+                .get_signed_public_key_trials(),
+            Some(1 << 12)
+        );
+        let more_trials_hss_key = HssPrivateKey::from(
+            &stored_more_trials_private_key,
+            &mut None,
+            &mut Default::default(),
+        )
+        .unwrap();
+        assert_eq!(hss_key.public_key, more_trials_hss_key.public_key);
+        assert!(
+            more_trials_hss_key.signatures[0]
+                .lmots_signature
+                .verify_chain_steps()
+                <= lmots_signature.verify_chain_steps()
+        );
+
+        // Only the counts of the encoding can be stored
+        assert!(ReferenceImplPrivateKey::generate(
+            &[
+                HssParameter::<H>::new(lmots, lms),
+                HssParameter::<H>::new(lmots, lms).with_signed_public_key_trials(1000),
+            ],
+            &seed,
+        )
+        .is_err());
+    }
+
     #[test]
     fn test_public_key_binary_representation() {
         let mut seed_and_lms_tree_identifier = SeedAndLmsTreeIdentifier::default();
@@ -390,6 +773,7 @@ mod tests {
             &HssParameter::construct_default_parameters(),
             &0,
             &mut None,
+            &mut Default::default(),
         );
         let public_key: HssPublicKey<Sha256_256> = HssPublicKey {
             level: 18,
@@ -0,0 +1,288 @@
+use crate::{
+    constants::{
+        EXTENDED_PARAMETER_SET_SIZE, ILEN, LMS_LEAF_IDENTIFIERS_SIZE,
+        MAX_DELEGATED_PRIVATE_KEY_SIZE,
+    },
+    hasher::{HashChain, HashOperations},
+    hss::{
+        aux::{hss_finalize_aux_data, hss_is_aux_data_used, MutableExpandedAuxData},
+        definitions::HssPrivateKey,
+        reference_impl_private_key::{
+            CompressedParameterSet, CompressedUsedLeafsIndexes, SeedAndLmsTreeIdentifier,
+        },
+        signing::{HssSignedPublicKey, InMemoryHssSignedPublicKey},
+    },
+    lms::generate_key_pair,
+    util::helper::read_and_advance,
+    HssParameter,
+};
+
+use core::{convert::TryInto, ops::Range};
+use tinyvec::ArrayVec;
+use zeroize::{Zeroize, ZeroizeOnDrop};
+
+/**
+The private key of a single tree of the second HSS level and its lower levels, which is handed out
+by the parent key without its seed.
+
+The public key of the delegated tree is signed by the top tree beforehand and stored within the
+key, so that the signatures verify under the public key of the parent key. The key is encoded as
+used leafs counter || parameter set of all levels (extended encoding) || I || seed ||
+verify chain steps of the signed public key || signed public key.
+ */
+#[derive(Default, Zeroize, ZeroizeOnDrop)]
+pub struct DelegatedPrivateKey<H: HashChain> {
+    // Signatures generated with the delegated tree
+    pub compressed_used_leafs_indexes: CompressedUsedLeafsIndexes,
+    pub compressed_parameter: CompressedParameterSet,
+    pub seed: SeedAndLmsTreeIdentifier<H>,
+    #[zeroize(skip)]
+    pub signed_public_key: HssSignedPublicKey<H>,
+}
+
+impl<H: HashChain> DelegatedPrivateKey<H> {
+    /// Signature indices of the parent key, which are covered by the tree `child_index`
+    pub fn parent_signatures(
+        parameters: &[HssParameter<H>],
+        child_index: u32,
+    ) -> Result<Range<u64>, ()> {
+        if parameters.len() < 2 {
+            return Err(());
+        }
+
+        let top_tree_height: u32 = parameters[0].get_lms_parameter().get_tree_height().into();
+        let lower_tree_heights = lower_tree_heights(parameters);
+        if top_tree_height + lower_tree_heights >= u64::BITS || child_index >= 1 << top_tree_height
+        {
+            return Err(());
+        }
+
+        let start = u64::from(child_index) << lower_tree_heights;
+        Ok(start..start + (1 << lower_tree_heights))
+    }
+
+    /**
+    Take over the tree of the second HSS level of `parent`, which must have been expanded for the
+    first signature of that tree.
+     */
+    pub fn from_parent(
+        compressed_parameter: &CompressedParameterSet,
+        parent: &HssPrivateKey<H>,
+    ) -> Result<Self, ()> {
+        let (lms_private_key, signature, public_key) = match (
+            parent.private_key.get(1),
+            parent.signatures.first(),
+            parent.public_key.first(),
+        ) {
+            (Some(lms_private_key), Some(signature), Some(public_key)) => {
+                (lms_private_key, signature, public_key)
+            }
+            _ => return Err(()),
+        };
+        if parent.private_key[1..]
+            .iter()
+            .any(|lms_private_key| lms_private_key.used_leafs_index != 0)
+        {
+            return Err(());
+        }
+
+        Ok(Self {
+            compressed_used_leafs_indexes: CompressedUsedLeafsIndexes::new(0),
+            compressed_parameter: compressed_parameter.clone(),
+            seed: SeedAndLmsTreeIdentifier::new(
+                &lms_private_key.seed,
+                &lms_private_key.lms_tree_identifier,
+            ),
+            signed_public_key: HssSignedPublicKey::new(signature.clone(), public_key.clone()),
+        })
+    }
+
+    fn wipe(&mut self) {
+        self.seed = SeedAndLmsTreeIdentifier::default();
+        self.compressed_parameter = CompressedParameterSet::default();
+        self.compressed_used_leafs_indexes = CompressedUsedLeafsIndexes::new(0);
+    }
+
+    /// Signature indices of the delegated tree and its lower levels, which are still unused
+    pub fn remaining_signatures(&self) -> Result<Range<u64>, ()> {
+        let parameters = self.compressed_parameter.to::<H>()?;
+
+        Ok(self.compressed_used_leafs_indexes.count()..1 << lower_tree_heights(&parameters))
+    }
+
+    /**
+    Expand the aux data of the delegated tree and its lower levels, which is authenticated with
+    the seed of the delegated tree. Unused aux data gets filled with the delegated tree right
+    away, like the aux data of a regular key during the key generation.
+     */
+    pub fn expand_aux_data<'a>(
+        &'a self,
+        aux_data: Option<&'a mut &mut [u8]>,
+        hash_operations: &mut HashOperations,
+    ) -> Result<Option<MutableExpandedAuxData<'a>>, ()> {
+        let parameters = self.compressed_parameter.to::<H>()?;
+        let used_leafs_indexes = self.compressed_used_leafs_indexes.to(&parameters);
+
+        let is_aux_data_used = if let Some(ref aux_data) = aux_data {
+            hss_is_aux_data_used(aux_data)
+        } else {
+            false
+        };
+
+        let mut expanded_aux_data = HssPrivateKey::get_expanded_aux_data(
+            aux_data,
+            self.seed.seed.as_slice(),
+            &parameters[1..],
+            is_aux_data_used,
+        );
+
+        if !is_aux_data_used && expanded_aux_data.is_some() {
+            generate_key_pair(
+                &self.seed,
+                &parameters[1],
+                &used_leafs_indexes[1],
+                &mut expanded_aux_data,
+                hash_operations,
+            );
+            if let Some(expanded_aux_data) = expanded_aux_data.as_mut() {
+                hss_finalize_aux_data::<H>(expanded_aux_data, self.seed.seed.as_slice());
+            }
+        }
+
+        Ok(expanded_aux_data)
+    }
+
+    pub fn to_hss_private_key(
+        &self,
+        aux_data: &mut Option<MutableExpandedAuxData>,
+        hash_operations: &mut HashOperations,
+    ) -> Result<HssPrivateKey<H>, ()> {
+        let parameters = self.compressed_parameter.to::<H>()?;
+        let used_leafs_indexes = self.compressed_used_leafs_indexes.to(&parameters);
+
+        let mut hss_private_key = HssPrivateKey::from_seed(
+            SeedAndLmsTreeIdentifier::new(&self.seed.seed, &self.seed.lms_tree_identifier),
+            &parameters[1..],
+            &used_leafs_indexes[1..parameters.len()],
+            self.seed.seed.as_slice(),
+            aux_data,
+            hash_operations,
+        )?;
+        hss_private_key.delegation = Some(self.signed_public_key.clone());
+
+        Ok(hss_private_key)
+    }
+
+    pub fn increment(&mut self) {
+        match self.remaining_signatures() {
+            Ok(remaining) if remaining.start + 1 < remaining.end => {
+                self.compressed_used_leafs_indexes =
+                    CompressedUsedLeafsIndexes::new(remaining.start + 1);
+            }
+            _ => self.wipe(),
+        }
+    }
+
+    pub fn to_binary_representation(&self) -> ArrayVec<[u8; MAX_DELEGATED_PRIVATE_KEY_SIZE]> {
+        let mut result = ArrayVec::new();
+
+        result.extend_from_slice(&self.compressed_used_leafs_indexes.count().to_be_bytes());
+        result.extend_from_slice(
+            self.compressed_parameter
+                .to_extended_binary_representation()
+                .as_slice(),
+        );
+        result.extend_from_slice(&self.seed.lms_tree_identifier);
+        result.extend_from_slice(self.seed.seed.as_slice());
+        result.extend_from_slice(
+            &self
+                .signed_public_key
+                .sig
+                .lmots_signature
+                .verify_chain_steps()
+                .to_be_bytes(),
+        );
+        result.extend_from_slice(self.signed_public_key.to_binary_representation().as_slice());
+
+        result
+    }
+
+    pub fn from_binary_representation(data: &[u8]) -> Result<Self, ()> {
+        let seed_len = H::OUTPUT_SIZE as usize;
+        if data.len()
+            <= LMS_LEAF_IDENTIFIERS_SIZE + EXTENDED_PARAMETER_SET_SIZE + ILEN + seed_len + 2
+        {
+            return Err(());
+        }
+
+        let mut result = Self::default();
+        let mut index = 0;
+
+        result.compressed_used_leafs_indexes = CompressedUsedLeafsIndexes::from_slice(
+            read_and_advance(data, LMS_LEAF_IDENTIFIERS_SIZE, &mut index),
+        );
+        result.compressed_parameter = CompressedParameterSet::from_slice(read_and_advance(
+            data,
+            EXTENDED_PARAMETER_SET_SIZE,
+            &mut index,
+        ))?;
+        let parameters = result.compressed_parameter.to::<H>()?;
+        if parameters.len() < 2 {
+            return Err(());
+        }
+
+        result
+            .seed
+            .lms_tree_identifier
+            .copy_from_slice(read_and_advance(data, ILEN, &mut index));
+        result
+            .seed
+            .seed
+            .as_mut_slice()
+            .copy_from_slice(read_and_advance(data, seed_len, &mut index));
+        let verify_chain_steps =
+            u16::from_be_bytes(read_and_advance(data, 2, &mut index).try_into().unwrap());
+
+        let signed_public_key = InMemoryHssSignedPublicKey::<H>::new(&data[index..]).ok_or(())?;
+        if signed_public_key.len() != data.len() - index {
+            return Err(());
+        }
+        result.signed_public_key = HssSignedPublicKey::new(
+            (&signed_public_key.sig).into(),
+            (&signed_public_key.public_key).into(),
+        );
+
+        // The delegated tree must belong to the second HSS level of the parameter set
+        let signed_public_key = &mut result.signed_public_key;
+        if signed_public_key.sig.lms_parameter != *parameters[0].get_lms_parameter()
+            || signed_public_key.sig.lmots_signature.lmots_parameter
+                != *parameters[0].get_lmots_parameter()
+            || signed_public_key.public_key.lms_parameter != *parameters[1].get_lms_parameter()
+            || signed_public_key.public_key.lmots_parameter != *parameters[1].get_lmots_parameter()
+            || signed_public_key.public_key.lms_tree_identifier != result.seed.lms_tree_identifier
+        {
+            return Err(());
+        }
+
+        let lmots_signature = &mut signed_public_key.sig.lmots_signature;
+        lmots_signature.hash_iterations = lmots_signature
+            .lmots_parameter
+            .get_max_hash_iterations()
+            .checked_sub(verify_chain_steps)
+            .ok_or(())?;
+
+        if result.remaining_signatures()?.is_empty() {
+            return Err(());
+        }
+
+        Ok(result)
+    }
+}
+
+fn lower_tree_heights<H: HashChain>(parameters: &[HssParameter<H>]) -> u32 {
+    parameters
+        .iter()
+        .skip(1)
+        .map(|parameter| u32::from(parameter.get_lms_parameter().get_tree_height()))
+        .sum()
+}
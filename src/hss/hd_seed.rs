@@ -0,0 +1,158 @@
+use crate::{
+    constants::{
+        LmsTreeIdentifier, D_HDSEED, HDSEED_CHILD, HDSEED_D, HDSEED_MASTER, HDSEED_PREFIX_LEN,
+        HDSEED_SEED, HDSEED_WHICH, MAX_HASH_SIZE,
+    },
+    hasher::{HashChain, HashOperations},
+    hss::reference_impl_private_key::{ReferenceImplPrivateKey, Seed},
+};
+
+use core::convert::{TryFrom, TryInto};
+use signature::Error;
+use tinyvec::ArrayVec;
+use zeroize::Zeroize;
+
+/**
+ * Derive the seed of an HSS key from `master_secret` and a path of labels, e.g.
+ * `&[b"product", b"production"]`, and return it together with the tree identifier of the top tree
+ * [`hss_keygen`](crate::hss::hss_keygen) will generate from the seed.
+ *
+ * The derivation is a chain of hashes with the prefix `0^20 || D_HDSEED || which`, where
+ * `D_HDSEED = 0xfcfc` separates it from the `D_TOPSEED` and `D_DAUX` derivations and the `0xff`
+ * marker of `SeedDerive`:
+ * ```text
+ * K_0 = H(prefix(0x00) || u16(len(master_secret)) || master_secret)
+ * K_i = H(prefix(0x01) || K_(i-1) || u16(len(label_i)) || label_i)
+ * seed = H(prefix(0x02) || K_n)
+ * ```
+ * Hence, a derived seed reveals neither the master secret nor the seeds of other paths.
+ * The master secret must be at least as long as the seed.
+ */
+pub fn hss_derive_seed<H: HashChain>(
+    master_secret: &[u8],
+    path: &[&[u8]],
+) -> Result<(Seed<H>, LmsTreeIdentifier), Error> {
+    if master_secret.len() < H::OUTPUT_SIZE as usize {
+        return Err(Error::new());
+    }
+
+    let mut chain_key = derive::<H>(HDSEED_MASTER, &[], master_secret)?;
+    for label in path {
+        let next_chain_key = derive::<H>(HDSEED_CHILD, &chain_key, label)?;
+        chain_key.zeroize();
+        chain_key = next_chain_key;
+    }
+
+    let mut private_key = ReferenceImplPrivateKey::<H>::default();
+    private_key.seed = Seed::try_from(
+        H::default()
+            .chain(prefix(HDSEED_SEED))
+            .chain(chain_key)
+            .finalize(),
+    )
+    .map_err(|_| Error::new())?;
+    chain_key.zeroize();
+
+    let lms_tree_identifier = private_key
+        .generate_root_seed_and_lms_tree_identifier(&mut HashOperations::default())
+        .lms_tree_identifier;
+
+    Ok((private_key.seed.clone(), lms_tree_identifier))
+}
+
+fn prefix(which: u8) -> [u8; HDSEED_PREFIX_LEN] {
+    let mut prefix = [0u8; HDSEED_PREFIX_LEN];
+
+    prefix[HDSEED_D] = (D_HDSEED >> 8) as u8;
+    prefix[HDSEED_D + 1] = (D_HDSEED & 0xff) as u8;
+    prefix[HDSEED_WHICH] = which;
+
+    prefix
+}
+
+fn derive<H: HashChain>(
+    which: u8,
+    chain_key: &[u8],
+    data: &[u8],
+) -> Result<ArrayVec<[u8; MAX_HASH_SIZE]>, Error> {
+    let data_len: u16 = data.len().try_into().map_err(|_| Error::new())?;
+
+    Ok(H::default()
+        .chain(prefix(which))
+        .chain(chain_key)
+        .chain(data_len.to_be_bytes())
+        .chain(data)
+        .finalize())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        hss::hss_keygen, HssParameter, LmotsAlgorithm, LmsAlgorithm, Sha256_256, Shake256_192,
+    };
+
+    const MASTER_SECRET: [u8; 32] = [
+        0x00, 0x01, 0x02, 0x03, 0x04, 0x05, 0x06, 0x07, 0x08, 0x09, 0x0a, 0x0b, 0x0c, 0x0d, 0x0e,
+        0x0f, 0x10, 0x11, 0x12, 0x13, 0x14, 0x15, 0x16, 0x17, 0x18, 0x19, 0x1a, 0x1b, 0x1c, 0x1d,
+        0x1e, 0x1f,
+    ];
+    const PATH: [&[u8]; 2] = [b"product", b"production"];
+
+    fn check_vector<H: HashChain>(path: &[&[u8]], seed: &str, lms_tree_identifier: &str) {
+        let (derived_seed, derived_lms_tree_identifier) =
+            hss_derive_seed::<H>(&MASTER_SECRET, path).unwrap();
+
+        assert_eq!(hex::encode(derived_seed.as_slice()), seed);
+        assert_eq!(
+            hex::encode(derived_lms_tree_identifier),
+            lms_tree_identifier
+        );
+    }
+
+    #[test]
+    fn test_vectors() {
+        check_vector::<Sha256_256>(
+            &[],
+            "8eed50d0e153e15823b9229b5dae6b60b39da5ac765abeced7422d14724e2593",
+            "59c1d6feda4d2d6475cdca5bb03a0297",
+        );
+        check_vector::<Sha256_256>(
+            &PATH,
+            "ff377d405b763bf2510500a5537225bf9977d75fcfcdfcd39690f423a55aa8c3",
+            "85510b460e74a41f080738a8340adff2",
+        );
+        check_vector::<Shake256_192>(
+            &[],
+            "41bcc634017f9aaef66f773a1fa01c51d99c6dedf06376b4",
+            "493270826804f19db6456355894d9651",
+        );
+        check_vector::<Shake256_192>(
+            &PATH,
+            "9cd002bd6654984533e417635418c4fa5eb1b83747bd72db",
+            "4af44fec1fea02179487c89d63c66b0f",
+        );
+    }
+
+    #[test]
+    fn derived_seed_for_keygen() {
+        type H = Sha256_256;
+        let parameters = [HssParameter::<H>::new(
+            LmotsAlgorithm::LmotsW4,
+            LmsAlgorithm::LmsH5,
+        )];
+
+        let (seed, lms_tree_identifier) = hss_derive_seed::<H>(&MASTER_SECRET, &PATH).unwrap();
+        let (_, verifying_key) = hss_keygen(&parameters, &seed, None).unwrap();
+        // L || LMS type || LM-OTS type || I || T[1]
+        assert_eq!(verifying_key.as_slice()[12..28], lms_tree_identifier);
+
+        // Labels are length-prefixed, so moving the boundary between labels changes the seed
+        let (other_seed, _) =
+            hss_derive_seed::<H>(&MASTER_SECRET, &[b"productp", b"roduction"]).unwrap();
+        assert_ne!(seed, other_seed);
+
+        assert!(hss_derive_seed::<H>(&MASTER_SECRET[..31], &PATH).is_err());
+        assert!(hss_derive_seed::<H>(&MASTER_SECRET, &[&[0u8; 1 << 16]]).is_err());
+    }
+}
@@ -1,34 +1,73 @@
+pub mod audit_log;
 pub mod aux;
 pub mod definitions;
+pub mod delegated_private_key;
+pub mod hd_seed;
 pub mod parameter;
 pub mod reference_impl_private_key;
+pub mod rollback;
 mod seed_derive;
 pub mod signing;
 pub mod verify;
 
-use core::{convert::TryFrom, marker::PhantomData};
+use core::{
+    cell::RefCell,
+    convert::{TryFrom, TryInto},
+    fmt,
+    marker::PhantomData,
+    ops::Range,
+};
+use digest::HashMarker;
+use subtle::ConstantTimeEq;
 use tinyvec::ArrayVec;
+use zeroize::{Zeroize, ZeroizeOnDrop, Zeroizing};
 
 use crate::{
-    constants::{MAX_HSS_PUBLIC_KEY_LENGTH, REF_IMPL_MAX_PRIVATE_KEY_SIZE},
-    hss::{aux::hss_is_aux_data_used, reference_impl_private_key::Seed},
-    signature::{Error, SignerMut, Verifier},
+    constants::{
+        D_PREHASH, MAX_BACKUP_KEY_SIZE, MAX_DELEGATED_PRIVATE_KEY_SIZE, MAX_HASH_SIZE,
+        MAX_HSS_PUBLIC_KEY_LENGTH, MAX_PRIVATE_KEY_SIZE, PREHASH_D, PREHASH_PREFIX_LEN,
+    },
+    hasher::{FullWidthHashChain, HashOperations},
+    hss::{
+        aux::{
+            hss_is_aux_data_used, hss_validate_aux_data, AuxDataError, AuxDataInfo,
+            MutableExpandedAuxData,
+        },
+        reference_impl_private_key::Seed,
+    },
+    signature::{DigestSigner, DigestVerifier, Error, Keypair, Signer, SignerMut, Verifier},
+    util::ArrayVecZeroize,
     HashChain, Signature, VerifierSignature,
 };
 
+#[cfg(feature = "fast_verify_core")]
+use crate::lm_ots::signing::FastVerifyOptions;
+#[cfg(feature = "fast_verify")]
+use rand::rngs::OsRng;
+#[cfg(feature = "rand_core")]
+use rand_core::{CryptoRng, RngCore};
+
 use self::{
+    audit_log::{AuditLog, AuditLogEntry},
     definitions::{HssPrivateKey, HssPublicKey, InMemoryHssPublicKey},
+    delegated_private_key::DelegatedPrivateKey,
     parameter::HssParameter,
-    reference_impl_private_key::ReferenceImplPrivateKey,
-    signing::{HssSignature, InMemoryHssSignature},
+    reference_impl_private_key::{CompressedUsedLeafsIndexes, ReferenceImplPrivateKey},
+    rollback::{MonotonicCounter, RollbackError},
+    signing::{HssSignature, InMemoryHssSignature, StreamSigner},
+    verify::{verify, StreamVerifier, VerificationCost, VerifierCache},
 };
 
 /**
  * Implementation of [`SignerMut`] using [`Signature`].
+ *
+ * The key holds the seed of all trees. It is zeroized on drop, redacted in its [`Debug`] output
+ * and compared in constant time. As the key is stateful, it doesn't implement [`Clone`], see
+ * [`SigningKey::duplicate`].
  */
-#[derive(Clone, Debug, PartialEq, Eq)]
+#[derive(Zeroize, ZeroizeOnDrop)]
 pub struct SigningKey<H: HashChain> {
-    pub bytes: ArrayVec<[u8; REF_IMPL_MAX_PRIVATE_KEY_SIZE]>,
+    bytes: ArrayVecZeroize<u8, MAX_PRIVATE_KEY_SIZE>,
     phantom_data: PhantomData<H>,
 }
 
@@ -37,34 +76,306 @@ impl<H: HashChain> SigningKey<H> {
         let bytes = ArrayVec::try_from(bytes).map_err(|_| Error::new())?;
 
         Ok(Self {
-            bytes,
+            bytes: ArrayVecZeroize(bytes),
             phantom_data: PhantomData,
         })
     }
 
-    pub fn as_slice(&self) -> &[u8] {
+    /**
+     * The encoded private key including the seed, e.g. to persist it after each signature. It
+     * must not leave the secure storage of the signer.
+     */
+    pub fn expose_secret(&self) -> &[u8] {
         self.bytes.as_slice()
     }
 
-    pub fn as_mut_slice(&mut self) -> &mut [u8] {
+    pub fn expose_secret_mut(&mut self) -> &mut [u8] {
         self.bytes.as_mut_slice()
     }
 
+    /**
+     * Copy this key including its state.
+     *
+     * Both copies sign with the same one-time keys, hence at most one of them may be used to sign
+     * afterwards. Meant to keep the persisted state of a key, e.g. to roll back a signature that
+     * never left the signer. To sign with multiple copies, see [`SigningKey::into_shards`].
+     */
+    pub fn duplicate(&self) -> Self {
+        Self {
+            bytes: self.bytes,
+            phantom_data: PhantomData,
+        }
+    }
+
     pub fn get_lifetime(&self) -> Result<u64, Error> {
         let rfc_sk = ReferenceImplPrivateKey::from_binary_representation(self.bytes.as_slice())
             .map_err(|_| Error::new())?;
 
-        let parsed_sk = HssPrivateKey::<H>::from(&rfc_sk, &mut None).map_err(|_| Error::new())?;
+        if rfc_sk.shard_range.is_some() {
+            let remaining = rfc_sk.remaining_signatures().map_err(|_| Error::new())?;
+            return Ok(remaining.end - remaining.start);
+        }
+
+        let parsed_sk =
+            HssPrivateKey::<H>::from(&rfc_sk, &mut None, &mut HashOperations::default())
+                .map_err(|_| Error::new())?;
 
         Ok(parsed_sk.get_lifetime())
     }
 
+    /**
+     * Global signature indices this key may still use, i.e. up to the end of its shard (see
+     * [`SigningKey::into_shards`]) or of the whole key.
+     */
+    pub fn signature_range(&self) -> Result<Range<u64>, Error> {
+        ReferenceImplPrivateKey::<H>::from_binary_representation(self.bytes.as_slice())
+            .and_then(|rfc_sk| rfc_sk.remaining_signatures())
+            .map_err(|_| Error::new())
+    }
+
+    /**
+     * Split the remaining signature indices of this key into `shard_count` disjoint ranges of
+     * (almost) equal size, e.g. to sign on multiple devices (see NIST SP 800-208, Section 7).
+     *
+     * Each shard is a [`SigningKey`] restricted to its range, which is stored within the key, and
+     * all shards verify under the [`VerifyingKey`] of this key. A shard is exhausted at the end of
+     * its range, which is reported by [`SigningKey::get_lifetime`], and can be split further.
+     *
+     * This key covers the ranges of all shards and must not be used any more afterwards.
+     */
+    pub fn into_shards(
+        self,
+        shard_count: u64,
+    ) -> Result<impl Iterator<Item = SigningKey<H>>, Error> {
+        let rfc_sk =
+            ReferenceImplPrivateKey::<H>::from_binary_representation(self.bytes.as_slice())
+                .map_err(|_| Error::new())?;
+        // Fails for too many shards
+        rfc_sk.shard(0, shard_count).map_err(|_| Error::new())?;
+
+        Ok((0..shard_count).map(move |shard_index| {
+            let shard = rfc_sk.shard(shard_index, shard_count).unwrap();
+            SigningKey::from_bytes(&shard.to_binary_representation()).unwrap()
+        }))
+    }
+
+    /**
+     * Load a signing key like [`SigningKey::from_bytes`], refusing it if it is behind `counter`
+     * or differs from the state recorded at the same count, e.g. because it was restored from a
+     * snapshot.
+     */
+    pub fn from_bytes_with_counter(
+        bytes: &[u8],
+        counter: &mut impl MonotonicCounter,
+    ) -> Result<Self, RollbackError> {
+        let signing_key = Self::from_bytes(bytes).map_err(|_| RollbackError::InvalidSigningKey)?;
+        signing_key.check_counter(counter)?;
+
+        Ok(signing_key)
+    }
+
+    pub fn check_counter(&self, counter: &mut impl MonotonicCounter) -> Result<(), RollbackError> {
+        rollback::check_counter::<H>(self.bytes.as_slice(), counter)
+    }
+
+    /**
+     * Record the current state of this key in `counter`, e.g. after key generation or after
+     * [`SigningKey::split_backup`], which change the key without signing.
+     */
+    pub fn commit_counter(&self, counter: &mut impl MonotonicCounter) -> Result<(), RollbackError> {
+        let used_signatures = rollback::used_signatures::<H>(self.bytes.as_slice())?;
+        rollback::commit_counter::<H>(used_signatures, self.bytes.as_slice(), counter)
+    }
+
+    /**
+     * Sign like [`SignerMut::try_sign`], after checking this key against `counter` (see
+     * [`SigningKey::check_counter`]). The advanced key is recorded in `counter` before the
+     * signature is returned, hence it has to be persisted before the signature is released.
+     * Otherwise the persisted key is refused as rolled back. The other signing functions don't
+     * consult `counter`, see [`MonotonicCounter`].
+     */
+    pub fn try_sign_with_counter(
+        &mut self,
+        msg: &[u8],
+        counter: &mut impl MonotonicCounter,
+    ) -> Result<Signature, Error> {
+        self.check_counter(counter)?;
+        let used_signatures = rollback::used_signatures::<H>(self.bytes.as_slice())?;
+
+        let signature = self.try_sign(msg)?;
+
+        // The key is wiped after its last signature, hence its count is not parsed again
+        rollback::commit_counter::<H>(used_signatures + 1, self.bytes.as_slice(), counter)?;
+
+        Ok(signature)
+    }
+
+    /**
+     * Sign like [`SignerMut::try_sign`] and append an [`AuditLogEntry`] for the signature to
+     * `audit_log`, which is chained to the last entry of the log. The signature is not returned if
+     * the entry can't be appended. `timestamp` is recorded with the entry. The other signing
+     * functions don't log their signatures, see [`AuditLog`].
+     */
+    pub fn try_sign_with_audit_log(
+        &mut self,
+        msg: &[u8],
+        timestamp: u64,
+        audit_log: &mut impl AuditLog,
+    ) -> Result<Signature, Error> {
+        let previous_chain_hash = audit_log.last_chain_hash()?;
+
+        let signature = self.try_sign(msg)?;
+
+        let entry = AuditLogEntry::new::<H>(
+            previous_chain_hash.as_deref(),
+            msg,
+            signature.as_ref(),
+            timestamp,
+        )?;
+        audit_log.append(&entry)?;
+
+        Ok(signature)
+    }
+
+    /**
+     * Split off the last `backup_signatures` signature indices of this key into a [`BackupKey`],
+     * which can be restored without the risk of reusing a leaf. This key is restricted to the
+     * indices below the backup and must be persisted before the backup is stored.
+     *
+     * `created_at` is recorded with the backup, e.g. as seconds since the Unix epoch.
+     */
+    pub fn split_backup(
+        &mut self,
+        backup_signatures: u64,
+        created_at: u64,
+    ) -> Result<BackupKey<H>, Error> {
+        let mut rfc_sk =
+            ReferenceImplPrivateKey::<H>::from_binary_representation(self.bytes.as_slice())
+                .map_err(|_| Error::new())?;
+        let remaining = rfc_sk.remaining_signatures().map_err(|_| Error::new())?;
+
+        let index = remaining
+            .end
+            .checked_sub(backup_signatures)
+            .ok_or_else(Error::new)?;
+        let backup = rfc_sk.split_off(index).map_err(|_| Error::new())?;
+
+        self.bytes = ArrayVecZeroize(
+            ArrayVec::try_from(rfc_sk.to_binary_representation().as_slice())
+                .map_err(|_| Error::new())?,
+        );
+
+        Ok(BackupKey {
+            signing_key: SigningKey::from_bytes(&backup.to_binary_representation())?,
+            created_at,
+        })
+    }
+
+    /**
+     * Hand out the tree `child_index` of the second HSS level as a [`DelegatedSigningKey`], which
+     * signs without the seed of this key (e.g. on a build agent). Its signatures verify under the
+     * [`VerifyingKey`] of this key.
+     *
+     * The public key of the delegated tree is signed by the top tree of this key, which is sped
+     * up by `aux_data`. All signatures of the delegated tree are consumed by this key, which must
+     * be persisted before the delegated key is handed out.
+     *
+     * Only the tree starting at the next signature index of this key can be delegated, e.g. the
+     * first tree of a new key or the tree after a previously delegated one. Hence, no signature of
+     * this key is skipped by the delegation, and its lifetime drops by exactly the signatures of
+     * the delegated tree. Other trees, including the one after a partially used tree, are refused.
+     */
+    pub fn delegate(
+        &mut self,
+        child_index: u32,
+        aux_data: Option<&mut &mut [u8]>,
+    ) -> Result<DelegatedSigningKey<H>, Error> {
+        let mut rfc_sk =
+            ReferenceImplPrivateKey::<H>::from_binary_representation(self.bytes.as_slice())
+                .map_err(|_| Error::new())?;
+        let parameters = rfc_sk
+            .compressed_parameter
+            .to::<H>()
+            .map_err(|_| Error::new())?;
+
+        let delegated_signatures =
+            DelegatedPrivateKey::<H>::parent_signatures(&parameters, child_index)
+                .map_err(|_| Error::new())?;
+        let remaining = rfc_sk.remaining_signatures().map_err(|_| Error::new())?;
+        if delegated_signatures.start != remaining.start || delegated_signatures.end > remaining.end
+        {
+            return Err(Error::new());
+        }
+
+        // Expand this key for the first signature of the delegated tree
+        let mut delegating_key = rfc_sk.clone();
+        delegating_key.compressed_used_leafs_indexes =
+            CompressedUsedLeafsIndexes::new(delegated_signatures.start);
+
+        let is_aux_data_used = aux_data
+            .as_ref()
+            .map_or(false, |aux_data| hss_is_aux_data_used(aux_data));
+        let mut expanded_aux_data = HssPrivateKey::get_expanded_aux_data(
+            aux_data,
+            delegating_key.seed.as_slice(),
+            &parameters,
+            is_aux_data_used,
+        );
+        let hss_private_key = HssPrivateKey::<H>::from(
+            &delegating_key,
+            &mut expanded_aux_data,
+            &mut HashOperations::default(),
+        )
+        .map_err(|_| Error::new())?;
+
+        let delegated_key = DelegatedPrivateKey::from_parent(
+            &delegating_key.compressed_parameter,
+            &hss_private_key,
+        )
+        .map_err(|_| Error::new())?;
+
+        rfc_sk
+            .skip_to(delegated_signatures.end)
+            .map_err(|_| Error::new())?;
+        self.bytes = ArrayVecZeroize(
+            ArrayVec::try_from(rfc_sk.to_binary_representation().as_slice())
+                .map_err(|_| Error::new())?,
+        );
+
+        DelegatedSigningKey::from_bytes(&delegated_key.to_binary_representation())
+    }
+
+    /**
+     * Recompute the [`VerifyingKey`] of this signing key, e.g. if the public key got lost.
+     *
+     * Supplying the `aux_data` of the key avoids regenerating the top tree. If `expected_key` is
+     * given, the recomputed key must match it, otherwise an error is returned.
+     */
+    pub fn verifying_key(
+        &self,
+        aux_data: Option<&mut &mut [u8]>,
+        expected_key: Option<&VerifyingKey<H>>,
+    ) -> Result<VerifyingKey<H>, Error> {
+        let rfc_sk = ReferenceImplPrivateKey::from_binary_representation(self.bytes.as_slice())
+            .map_err(|_| Error::new())?;
+
+        let hss_public_key =
+            HssPublicKey::<H>::from(&rfc_sk, aux_data, &mut HashOperations::default())
+                .map_err(|_| Error::new())?;
+        let verifying_key = VerifyingKey::from_bytes(&hss_public_key.to_binary_representation())?;
+
+        match expected_key {
+            Some(expected_key) if *expected_key != verifying_key => Err(Error::new()),
+            _ => Ok(verifying_key),
+        }
+    }
+
     pub fn try_sign_with_aux(
         &mut self,
         msg: &[u8],
         aux_data: Option<&mut &mut [u8]>,
     ) -> Result<Signature, Error> {
-        let private_key = self.bytes;
+        let private_key = Zeroizing::new(self.bytes);
         let mut private_key_update_function = |new_key: &[u8]| {
             self.bytes.as_mut_slice().copy_from_slice(new_key);
             Ok(())
@@ -77,14 +388,362 @@ impl<H: HashChain> SigningKey<H> {
             aux_data,
         )
     }
+
+    /**
+     * Sign like [`SigningKey::try_sign_with_aux`], but fail instead of silently regenerating the
+     * top tree if `aux_data` is unused or corrupted. No leaf is reserved in that case, the reason
+     * is reported by [`SigningKey::validate_aux`].
+     */
+    pub fn try_sign_with_required_aux(
+        &mut self,
+        msg: &[u8],
+        aux_data: &mut &mut [u8],
+    ) -> Result<Signature, Error> {
+        if is_prehash_message(msg) {
+            return Err(Error::new());
+        }
+
+        let private_key = Zeroizing::new(self.bytes);
+        let mut private_key_update_function = |new_key: &[u8]| {
+            self.bytes.as_mut_slice().copy_from_slice(new_key);
+            Ok(())
+        };
+
+        hss_sign_core::<H>(
+            private_key.as_slice(),
+            &mut private_key_update_function,
+            Some(aux_data),
+            true,
+            |private_key, expanded_aux_data, hash_operations| {
+                HssSignature::sign(private_key, msg, expanded_aux_data, hash_operations)
+            },
+        )
+    }
+
+    /**
+     * Check that `aux_data` was created for this signing key and is intact, e.g. after loading it
+     * from a file. See [`AuxDataError`] for the reasons of a rejection.
+     */
+    pub fn validate_aux(&self, aux_data: &[u8]) -> Result<AuxDataInfo, AuxDataError> {
+        let rfc_sk =
+            ReferenceImplPrivateKey::<H>::from_binary_representation(self.bytes.as_slice())
+                .map_err(|_| AuxDataError::InvalidSigningKey)?;
+        let parameters = rfc_sk
+            .compressed_parameter
+            .to::<H>()
+            .map_err(|_| AuxDataError::InvalidSigningKey)?;
+
+        hss_validate_aux_data::<H>(&parameters, aux_data, rfc_sk.seed.as_slice())
+    }
+
+    /**
+     * Rebuild the aux data of this signing key from its seed, e.g. if it was lost or failed
+     * [`SigningKey::validate_aux`]. The whole buffer is overwritten and shrunk like by
+     * [`crate::keygen`], hence its length is the size budget of the aux data.
+     */
+    pub fn regenerate_aux(&self, aux_data: &mut &mut [u8]) -> Result<AuxDataInfo, Error> {
+        if aux_data.is_empty() {
+            return Err(Error::new());
+        }
+        aux_data.fill(0);
+
+        let rfc_sk = ReferenceImplPrivateKey::from_binary_representation(self.bytes.as_slice())
+            .map_err(|_| Error::new())?;
+        HssPublicKey::<H>::from(&rfc_sk, Some(aux_data), &mut HashOperations::default())
+            .map_err(|_| Error::new())?;
+
+        Ok(self.validate_aux(aux_data)?)
+    }
+
+    /**
+     * Sign a message that was hashed beforehand by `digest`, which avoids buffering the message.
+     * To sign a message without buffering it and without a separate mode, use
+     * [`SigningKey::begin_sign`] instead.
+     *
+     * This is a separate prehash mode: the signed message is
+     * `0^20 || D_PREHASH || digest` with `D_PREHASH = 0xf8f8`, of which the LMS message hash
+     * `H(I || q || D_MESG || C || message)` is computed as usual. The signature only verifies with
+     * [`DigestVerifier::verify_digest`], but not with the original message or the digest as
+     * message. All other ways of signing refuse messages starting with `0^20 || D_PREHASH`, so
+     * that they can't be used to obtain a signature of this mode.
+     *
+     * The security of the signature rests on the collision resistance of `digest`, which is not
+     * protected by the randomizer `C`. Hence, the mode is limited to the hash functions with
+     * 256 bit output ([`FullWidthHashChain`]).
+     */
+    pub fn try_sign_digest(&mut self, digest: H) -> Result<Signature, Error>
+    where
+        H: FullWidthHashChain,
+    {
+        let message = prehash_message(digest);
+        let private_key = Zeroizing::new(self.bytes);
+        let mut private_key_update_function = |new_key: &[u8]| {
+            self.bytes.as_mut_slice().copy_from_slice(new_key);
+            Ok(())
+        };
+
+        hss_sign_core::<H>(
+            private_key.as_slice(),
+            &mut private_key_update_function,
+            None,
+            false,
+            |private_key, expanded_aux_data, hash_operations| {
+                HssSignature::sign(
+                    private_key,
+                    message.as_slice(),
+                    expanded_aux_data,
+                    hash_operations,
+                )
+            },
+        )
+    }
+
+    /**
+     * Start signing a message that is fed incrementally. See [`hss_begin_sign`].
+     */
+    pub fn begin_sign(&mut self) -> Result<StreamSigner<H>, Error> {
+        self.begin_sign_with_aux(None)
+    }
+
+    pub fn begin_sign_with_aux(
+        &mut self,
+        aux_data: Option<&mut &mut [u8]>,
+    ) -> Result<StreamSigner<H>, Error> {
+        let private_key = Zeroizing::new(self.bytes);
+        let mut private_key_update_function = |new_key: &[u8]| {
+            self.bytes.as_mut_slice().copy_from_slice(new_key);
+            Ok(())
+        };
+
+        hss_begin_sign::<H>(
+            private_key.as_slice(),
+            &mut private_key_update_function,
+            aux_data,
+        )
+    }
+}
+
+impl<H: HashChain> fmt::Debug for SigningKey<H> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("SigningKey").finish_non_exhaustive()
+    }
+}
+
+impl<H: HashChain> PartialEq for SigningKey<H> {
+    fn eq(&self, other: &Self) -> bool {
+        self.bytes.as_slice().ct_eq(other.bytes.as_slice()).into()
+    }
 }
 
+impl<H: HashChain> Eq for SigningKey<H> {}
+
 impl<H: HashChain> SignerMut<Signature> for SigningKey<H> {
     fn try_sign(&mut self, msg: &[u8]) -> Result<Signature, Error> {
         self.try_sign_with_aux(msg, None)
     }
 }
 
+/**
+ * Backup of a [`SigningKey`], which is restricted to signature indices the original key never
+ * uses. Created by [`SigningKey::split_backup`].
+ */
+#[derive(Debug, PartialEq, Eq)]
+pub struct BackupKey<H: HashChain> {
+    pub signing_key: SigningKey<H>,
+    /// Time of the backup as supplied to [`SigningKey::split_backup`]
+    pub created_at: u64,
+}
+
+impl<H: HashChain> BackupKey<H> {
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, Error> {
+        if bytes.len() < 8 {
+            return Err(Error::new());
+        }
+        let (created_at, signing_key) = bytes.split_at(8);
+
+        let backup = Self {
+            signing_key: SigningKey::from_bytes(signing_key)?,
+            created_at: u64::from_be_bytes(created_at.try_into().unwrap()),
+        };
+        backup.backup_range()?;
+
+        Ok(backup)
+    }
+
+    pub fn to_bytes(&self) -> ArrayVec<[u8; MAX_BACKUP_KEY_SIZE]> {
+        let mut result = ArrayVec::new();
+
+        result.extend_from_slice(&self.created_at.to_be_bytes());
+        result.extend_from_slice(self.signing_key.expose_secret());
+
+        result
+    }
+
+    /// Signature indices split off into the backup, independent of their use after the restore
+    pub fn backup_range(&self) -> Result<Range<u64>, Error> {
+        ReferenceImplPrivateKey::<H>::from_binary_representation(self.signing_key.expose_secret())
+            .ok()
+            .and_then(|rfc_sk| rfc_sk.shard_range)
+            .map(|shard_range| shard_range.start..shard_range.end)
+            .ok_or_else(Error::new)
+    }
+}
+
+/**
+ * Signing key of a single tree of the second HSS level and the levels below, which got handed
+ * out by [`SigningKey::delegate`]. It holds neither the seed nor any other tree of the parent
+ * key, but its signatures verify under the [`VerifyingKey`] of the parent key.
+ *
+ * Like [`SigningKey`], the key is zeroized on drop, redacted in its [`Debug`] output and doesn't
+ * implement [`Clone`].
+ */
+#[derive(Zeroize, ZeroizeOnDrop)]
+pub struct DelegatedSigningKey<H: HashChain> {
+    bytes: ArrayVecZeroize<u8, MAX_DELEGATED_PRIVATE_KEY_SIZE>,
+    phantom_data: PhantomData<H>,
+}
+
+impl<H: HashChain> DelegatedSigningKey<H> {
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, Error> {
+        let bytes = ArrayVec::try_from(bytes).map_err(|_| Error::new())?;
+
+        Ok(Self {
+            bytes: ArrayVecZeroize(bytes),
+            phantom_data: PhantomData,
+        })
+    }
+
+    /// The encoded private key including the seed of the delegated tree
+    pub fn expose_secret(&self) -> &[u8] {
+        self.bytes.as_slice()
+    }
+
+    /// Signatures left, before the delegated tree is exhausted
+    pub fn get_lifetime(&self) -> Result<u64, Error> {
+        let remaining = DelegatedPrivateKey::<H>::from_binary_representation(self.bytes.as_slice())
+            .and_then(|private_key| private_key.remaining_signatures())
+            .map_err(|_| Error::new())?;
+
+        Ok(remaining.end - remaining.start)
+    }
+
+    /**
+     * Sign like [`SigningKey::try_sign_with_aux`]. The aux data belongs to the delegated tree and
+     * can't be shared with the parent key. Unused aux data gets filled with the delegated tree
+     * on the first signature, so that the following signatures don't regenerate it.
+     */
+    pub fn try_sign_with_aux(
+        &mut self,
+        msg: &[u8],
+        aux_data: Option<&mut &mut [u8]>,
+    ) -> Result<Signature, Error> {
+        if is_prehash_message(msg) {
+            return Err(Error::new());
+        }
+
+        let mut private_key =
+            DelegatedPrivateKey::<H>::from_binary_representation(self.bytes.as_slice())
+                .map_err(|_| Error::new())?;
+
+        let mut hash_operations = HashOperations::default();
+        let mut expanded_aux_data = private_key
+            .expand_aux_data(aux_data, &mut hash_operations)
+            .map_err(|_| Error::new())?;
+        let mut hss_private_key = private_key
+            .to_hss_private_key(&mut expanded_aux_data, &mut hash_operations)
+            .map_err(|_| Error::new())?;
+        let hss_signature = HssSignature::sign(
+            &mut hss_private_key,
+            msg,
+            &mut expanded_aux_data,
+            &mut hash_operations,
+        )
+        .map_err(|_| Error::new())?;
+
+        // Advance private key
+        private_key.increment();
+        self.bytes = ArrayVecZeroize(
+            ArrayVec::try_from(private_key.to_binary_representation().as_slice())
+                .map_err(|_| Error::new())?,
+        );
+
+        hss_signature.to_signature(hash_operations)
+    }
+}
+
+impl<H: HashChain> SignerMut<Signature> for DelegatedSigningKey<H> {
+    fn try_sign(&mut self, msg: &[u8]) -> Result<Signature, Error> {
+        self.try_sign_with_aux(msg, None)
+    }
+}
+
+impl<H: HashChain> fmt::Debug for DelegatedSigningKey<H> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("DelegatedSigningKey")
+            .finish_non_exhaustive()
+    }
+}
+
+/**
+ * [`SigningKey`] together with its [`VerifyingKey`], implementing [`Keypair`], [`Signer`] and
+ * [`DigestSigner`].
+ *
+ * These traits sign through a shared reference, hence the signing key is kept in a [`RefCell`]
+ * and advanced in place. As with [`SignerMut`], the advanced signing key has to be persisted
+ * after each signature, see [`SigningKeypair::signing_key`].
+ */
+#[derive(Debug)]
+pub struct SigningKeypair<H: HashChain> {
+    signing_key: RefCell<SigningKey<H>>,
+    verifying_key: VerifyingKey<H>,
+}
+
+impl<H: HashChain> SigningKeypair<H> {
+    /**
+     * Combine a signing key with its verifying key. The keys are not checked to belong together.
+     */
+    pub fn new(signing_key: SigningKey<H>, verifying_key: VerifyingKey<H>) -> Self {
+        Self {
+            signing_key: RefCell::new(signing_key),
+            verifying_key,
+        }
+    }
+
+    /**
+     * Copy of the current state of the signing key (see [`SigningKey::duplicate`]) to persist it
+     * after each signature. The copy must not be used to sign.
+     */
+    pub fn signing_key(&self) -> SigningKey<H> {
+        self.signing_key.borrow().duplicate()
+    }
+
+    pub fn into_parts(self) -> (SigningKey<H>, VerifyingKey<H>) {
+        (self.signing_key.into_inner(), self.verifying_key)
+    }
+}
+
+impl<H: HashChain> AsRef<VerifyingKey<H>> for SigningKeypair<H> {
+    fn as_ref(&self) -> &VerifyingKey<H> {
+        &self.verifying_key
+    }
+}
+
+impl<H: HashChain> Keypair<Signature> for SigningKeypair<H> {
+    type VerifyingKey = VerifyingKey<H>;
+}
+
+impl<H: HashChain> Signer<Signature> for SigningKeypair<H> {
+    fn try_sign(&self, msg: &[u8]) -> Result<Signature, Error> {
+        self.signing_key.borrow_mut().try_sign(msg)
+    }
+}
+
+impl<H: FullWidthHashChain + HashMarker> DigestSigner<H, Signature> for SigningKeypair<H> {
+    fn try_sign_digest(&self, digest: H) -> Result<Signature, Error> {
+        self.signing_key.borrow_mut().try_sign_digest(digest)
+    }
+}
+
 /**
  * Implementation of [`Verifier`] using [`Signature`] or [`VerifierSignature`].
  */
@@ -107,6 +766,71 @@ impl<H: HashChain> VerifyingKey<H> {
     pub fn as_slice(&self) -> &[u8] {
         self.bytes.as_slice()
     }
+
+    /**
+     * Start the verification of `signature` for a message that is fed incrementally.
+     *
+     * The message is passed to the returned [`StreamVerifier`] using [`digest::Update`], the
+     * verification is completed with [`StreamVerifier::finalize`].
+     */
+    pub fn begin_verify<'a, S: AsRef<[u8]> + ?Sized>(
+        &'a self,
+        signature: &'a S,
+    ) -> Result<StreamVerifier<'a, H>, Error> {
+        let signature =
+            InMemoryHssSignature::<H>::new(signature.as_ref()).ok_or_else(Error::new)?;
+        let public_key = InMemoryHssPublicKey::<H>::new(&self.bytes).ok_or_else(Error::new)?;
+
+        Ok(StreamVerifier::new(signature, public_key))
+    }
+
+    /**
+     * Verify `signature` for the message read from `reader` until its end.
+     */
+    #[cfg(feature = "std")]
+    pub fn verify_reader<R: std::io::Read + ?Sized, S: AsRef<[u8]> + ?Sized>(
+        &self,
+        reader: &mut R,
+        signature: &S,
+    ) -> Result<(), Error> {
+        let mut verifier = self.begin_verify(signature)?;
+        let mut buffer = [0u8; 4096];
+
+        loop {
+            match reader.read(&mut buffer) {
+                Ok(0) => break,
+                Ok(read) => digest::Update::update(&mut verifier, &buffer[..read]),
+                Err(error) if error.kind() == std::io::ErrorKind::Interrupted => continue,
+                Err(_) => return Err(Error::new()),
+            }
+        }
+
+        verifier.finalize()
+    }
+
+    /**
+     * Verify a signature like [`Verifier::verify`], skipping signed public keys already
+     * validated through `cache`. See [`hss_verify_cached`].
+     */
+    pub fn verify_cached<const N: usize>(
+        &self,
+        msg: &[u8],
+        signature: &[u8],
+        cache: &mut VerifierCache<H, N>,
+    ) -> Result<(), Error> {
+        hss_verify_cached::<H, N>(msg, signature, &self.bytes, cache)
+    }
+
+    /**
+     * Verify a batch of `(message, signature)` pairs. See [`hss_verify_batch`].
+     */
+    pub fn verify_batch<const N: usize>(
+        &self,
+        messages_and_signatures: &[(&[u8], &[u8])],
+        cache: &mut VerifierCache<H, N>,
+    ) -> Result<(), Error> {
+        hss_verify_batch::<H, N>(messages_and_signatures, &self.bytes, cache)
+    }
 }
 
 impl<H: HashChain> Verifier<Signature> for VerifyingKey<H> {
@@ -121,6 +845,49 @@ impl<'a, H: HashChain> Verifier<VerifierSignature<'a>> for VerifyingKey<H> {
     }
 }
 
+impl<H: FullWidthHashChain + HashMarker> DigestVerifier<H, Signature> for VerifyingKey<H> {
+    fn verify_digest(&self, digest: H, signature: &Signature) -> Result<(), Error> {
+        self.verify(prehash_message(digest).as_slice(), signature)
+    }
+}
+
+impl<'a, H: FullWidthHashChain + HashMarker> DigestVerifier<H, VerifierSignature<'a>>
+    for VerifyingKey<H>
+{
+    fn verify_digest(&self, digest: H, signature: &VerifierSignature) -> Result<(), Error> {
+        self.verify(prehash_message(digest).as_slice(), signature)
+    }
+}
+
+/// Message signed in the prehash mode for `digest`, see [`SigningKey::try_sign_digest`]
+fn prehash_message<H: FullWidthHashChain>(
+    digest: H,
+) -> ArrayVec<[u8; PREHASH_PREFIX_LEN + MAX_HASH_SIZE]> {
+    let mut message = ArrayVec::from_array_len(
+        [0u8; PREHASH_PREFIX_LEN + MAX_HASH_SIZE],
+        PREHASH_PREFIX_LEN,
+    );
+
+    message[..PREHASH_PREFIX_LEN].copy_from_slice(&prehash_prefix());
+    message.extend_from_slice(HashChain::finalize(digest).as_slice());
+
+    message
+}
+
+fn prehash_prefix() -> [u8; PREHASH_PREFIX_LEN] {
+    let mut prefix = [0u8; PREHASH_PREFIX_LEN];
+
+    prefix[PREHASH_D] = (D_PREHASH >> 8) as u8;
+    prefix[PREHASH_D + 1] = (D_PREHASH & 0xff) as u8;
+
+    prefix
+}
+
+/// Whether `message` is reserved for the prehash mode and hence refused by all other signing
+pub(crate) fn is_prehash_message(message: &[u8]) -> bool {
+    message.starts_with(&prehash_prefix())
+}
+
 /**
  * Verify a signature ([`Signature`] or [`VerifierSignature`]).
  *
@@ -135,14 +902,145 @@ pub fn hss_verify<H: HashChain>(
     signature: &[u8],
     public_key: &[u8],
 ) -> Result<(), Error> {
-    let signature = InMemoryHssSignature::<H>::new(signature).ok_or_else(Error::new)?;
-    let public_key = InMemoryHssPublicKey::<H>::new(public_key).ok_or_else(Error::new)?;
-
-    crate::hss::verify::verify(&signature, &public_key, message).map_err(|_| Error::new())
-}
+    hss_verify_core::<H, 0>(
+        message,
+        signature,
+        public_key,
+        &mut VerifierCache::new(),
+        &mut Default::default(),
+        &mut HashOperations::default(),
+    )
+}
+
+/**
+ * Verify a signature like [`hss_verify`] and report the chain steps and tree hashes the
+ * verification took.
+ */
+pub fn hss_verify_with_cost<H: HashChain>(
+    message: &[u8],
+    signature: &[u8],
+    public_key: &[u8],
+) -> Result<VerificationCost, Error> {
+    hss_verify_cached_with_cost::<H, 0>(message, signature, public_key, &mut VerifierCache::new())
+}
+
+/**
+ * Verify a signature like [`hss_verify_cached`] and report the chain steps and tree hashes the
+ * verification took. Signed public keys found in `cache` do not add to the cost.
+ */
+pub fn hss_verify_cached_with_cost<H: HashChain, const N: usize>(
+    message: &[u8],
+    signature: &[u8],
+    public_key: &[u8],
+    cache: &mut VerifierCache<H, N>,
+) -> Result<VerificationCost, Error> {
+    let mut cost = VerificationCost::default();
+    hss_verify_core::<H, N>(
+        message,
+        signature,
+        public_key,
+        cache,
+        &mut cost,
+        &mut HashOperations::default(),
+    )?;
+    Ok(cost)
+}
+
+/**
+ * Verify a signature like [`hss_verify`], but only check the signed public keys of lower HSS
+ * levels that are not yet in `cache`. Successfully verified signed public keys are added to the
+ * cache, so that subsequent signatures of the same subtree only need the bottom level signature
+ * to be checked.
+ *
+ * # Arguments
+ * * `HashChain` - The hasher implementation that should be used. ```Sha256``` is a standard software implementation.
+ * * `message` - The message that should be verified.
+ * * `signature` - The signature that should be used for verification.
+ * * `public_key` - The public key that should be used for verification.
+ * * `cache` - The cache of already verified signed public keys. It may be shared between public keys.
+ */
+pub fn hss_verify_cached<H: HashChain, const N: usize>(
+    message: &[u8],
+    signature: &[u8],
+    public_key: &[u8],
+    cache: &mut VerifierCache<H, N>,
+) -> Result<(), Error> {
+    hss_verify_core::<H, N>(
+        message,
+        signature,
+        public_key,
+        cache,
+        &mut Default::default(),
+        &mut HashOperations::default(),
+    )
+}
 
 /**
- * Generate a [`Signature`].
+ * Verify a batch of signatures of the same public key. Fails if any of the signatures is invalid.
+ *
+ * # Arguments
+ * * `HashChain` - The hasher implementation that should be used. ```Sha256``` is a standard software implementation.
+ * * `messages_and_signatures` - The messages together with their signatures.
+ * * `public_key` - The public key that should be used for verification.
+ * * `cache` - The cache of already verified signed public keys, see [`hss_verify_cached`].
+ */
+pub fn hss_verify_batch<H: HashChain, const N: usize>(
+    messages_and_signatures: &[(&[u8], &[u8])],
+    public_key: &[u8],
+    cache: &mut VerifierCache<H, N>,
+) -> Result<(), Error> {
+    for (message, signature) in messages_and_signatures.iter() {
+        hss_verify_cached::<H, N>(message, signature, public_key, cache)?;
+    }
+    Ok(())
+}
+
+/**
+ * Verify a signature like [`hss_verify`] and report the hash operations the verification took.
+ */
+#[cfg(feature = "verbose")]
+pub fn hss_verify_verbose<H: HashChain>(
+    message: &[u8],
+    signature: &[u8],
+    public_key: &[u8],
+) -> Result<HashOperations, Error> {
+    let mut hash_operations = HashOperations::default();
+    hss_verify_core::<H, 0>(
+        message,
+        signature,
+        public_key,
+        &mut VerifierCache::new(),
+        &mut Default::default(),
+        &mut hash_operations,
+    )?;
+    Ok(hash_operations)
+}
+
+fn hss_verify_core<H: HashChain, const N: usize>(
+    message: &[u8],
+    signature: &[u8],
+    public_key: &[u8],
+    cache: &mut VerifierCache<H, N>,
+    cost: &mut VerificationCost,
+    hash_operations: &mut HashOperations,
+) -> Result<(), Error> {
+    let signature = InMemoryHssSignature::<H>::new(signature).ok_or_else(Error::new)?;
+    let public_key = InMemoryHssPublicKey::<H>::new(public_key).ok_or_else(Error::new)?;
+
+    verify(
+        &signature,
+        &public_key,
+        message,
+        cache,
+        cost,
+        hash_operations,
+    )
+    .map_err(|_| Error::new())
+}
+
+/**
+ * Generate a [`Signature`]. Messages starting with `0^20 || D_PREHASH` are refused, as these are
+ * reserved for [`SigningKey::try_sign_digest`].
  *
  * # Arguments
  * * `HashChain` - The hasher implementation that should be used. ```Sha256``` is a standard software implementation.
@@ -151,30 +1049,72 @@ pub fn hss_verify<H: HashChain>(
  * * `private_key_update_function` - The update function that is called with the new private key. This function should save the new private key.
  * * `aux_data` - Auxiliary data to speedup signature generation if available
  */
-
 pub fn hss_sign<H: HashChain>(
     message: &[u8],
     private_key: &[u8],
     private_key_update_function: &mut dyn FnMut(&[u8]) -> Result<(), ()>,
     aux_data: Option<&mut &mut [u8]>,
 ) -> Result<Signature, Error> {
+    if is_prehash_message(message) {
+        return Err(Error::new());
+    }
+
     hss_sign_core::<H>(
-        Some(message),
-        None,
         private_key,
         private_key_update_function,
         aux_data,
+        false,
+        |private_key, expanded_aux_data, hash_operations| {
+            HssSignature::sign(private_key, message, expanded_aux_data, hash_operations)
+        },
     )
 }
 
+/**
+ * Generate a fast verifiable [`Signature`] like [`hss_sign_mut_with_rng`], using the randomness
+ * of the operating system and splitting the effort to `FastVerifyOptions::threads`.
+ */
 #[cfg(feature = "fast_verify")]
 pub fn hss_sign_mut<H: HashChain>(
     message_mut: &mut [u8],
     private_key: &[u8],
     private_key_update_function: &mut dyn FnMut(&[u8]) -> Result<(), ()>,
     aux_data: Option<&mut &mut [u8]>,
+    fast_verify_options: &FastVerifyOptions,
 ) -> Result<Signature, Error> {
-    if message_mut.len() <= H::OUTPUT_SIZE.into() {
+    hss_sign_mut_with_rng::<H>(
+        message_mut,
+        private_key,
+        private_key_update_function,
+        aux_data,
+        fast_verify_options,
+        &mut OsRng,
+    )
+}
+
+/**
+ * Generate a fast verifiable [`Signature`].
+ *
+ * The last `HashChain::OUTPUT_SIZE` bytes of `message_mut` must be zero and are replaced by the
+ * randomizer that minimizes the verification effort. The effort spent on finding it is set by
+ * `fast_verify_options`, the randomizers tried are seeded from `rng`. Without the `fast_verify`
+ * feature (e.g. on `no_std`), all randomizers are tried on the calling thread. Zero threads are
+ * rejected before a leaf is used.
+ */
+#[cfg(feature = "fast_verify_core")]
+pub fn hss_sign_mut_with_rng<H: HashChain>(
+    message_mut: &mut [u8],
+    private_key: &[u8],
+    private_key_update_function: &mut dyn FnMut(&[u8]) -> Result<(), ()>,
+    aux_data: Option<&mut &mut [u8]>,
+    fast_verify_options: &FastVerifyOptions,
+    rng: &mut (impl CryptoRng + RngCore),
+) -> Result<Signature, Error> {
+    if message_mut.len() <= H::OUTPUT_SIZE.into() || is_prehash_message(message_mut) {
+        return Err(Error::new());
+    }
+    #[cfg(feature = "fast_verify")]
+    if fast_verify_options.threads == 0 {
         return Err(Error::new());
     }
 
@@ -184,21 +1124,67 @@ pub fn hss_sign_mut<H: HashChain>(
     }
 
     hss_sign_core::<H>(
-        None,
-        Some(message_mut),
         private_key,
         private_key_update_function,
         aux_data,
+        false,
+        |private_key, expanded_aux_data, hash_operations| {
+            HssSignature::sign_fast_verify(
+                private_key,
+                message_mut,
+                fast_verify_options,
+                rng,
+                expanded_aux_data,
+                hash_operations,
+            )
+        },
     )
 }
 
 fn hss_sign_core<H: HashChain>(
-    message: Option<&[u8]>,
-    message_mut: Option<&mut [u8]>,
     private_key: &[u8],
     private_key_update_function: &mut dyn FnMut(&[u8]) -> Result<(), ()>,
     aux_data: Option<&mut &mut [u8]>,
+    require_aux_data: bool,
+    sign: impl FnOnce(
+        &mut HssPrivateKey<H>,
+        &mut Option<MutableExpandedAuxData>,
+        &mut HashOperations,
+    ) -> Result<HssSignature<H>, ()>,
 ) -> Result<Signature, Error> {
+    let mut hash_operations = HashOperations::default();
+
+    let hss_signature = hss_advance_private_key::<H, _>(
+        private_key,
+        private_key_update_function,
+        aux_data,
+        require_aux_data,
+        &mut hash_operations,
+        sign,
+    )?;
+
+    hss_signature.to_signature(hash_operations)
+}
+
+/**
+ * Reconstruct the HSS private key, let `use_private_key` reserve a leaf of it and save the
+ * advanced private key, before the result of `use_private_key` is returned.
+ *
+ * If `require_aux_data` is set, unused or corrupted aux data fails before any leaf is reserved,
+ * instead of silently regenerating the top tree.
+ */
+fn hss_advance_private_key<H: HashChain, T>(
+    private_key: &[u8],
+    private_key_update_function: &mut dyn FnMut(&[u8]) -> Result<(), ()>,
+    aux_data: Option<&mut &mut [u8]>,
+    require_aux_data: bool,
+    hash_operations: &mut HashOperations,
+    use_private_key: impl FnOnce(
+        &mut HssPrivateKey<H>,
+        &mut Option<MutableExpandedAuxData>,
+        &mut HashOperations,
+    ) -> Result<T, ()>,
+) -> Result<T, Error> {
     let mut rfc_private_key = ReferenceImplPrivateKey::from_binary_representation(private_key)
         .map_err(|_| Error::new())?;
 
@@ -207,6 +1193,9 @@ fn hss_sign_core<H: HashChain>(
     } else {
         false
     };
+    if require_aux_data && !is_aux_data_used {
+        return Err(Error::new());
+    }
 
     let parameters = rfc_private_key
         .compressed_parameter
@@ -214,36 +1203,56 @@ fn hss_sign_core<H: HashChain>(
         .map_err(|_| Error::new())?;
     let mut expanded_aux_data = HssPrivateKey::get_expanded_aux_data(
         aux_data,
-        &rfc_private_key,
-        parameters[0].get_lms_parameter(),
+        rfc_private_key.seed.as_slice(),
+        &parameters,
         is_aux_data_used,
     );
+    if require_aux_data && expanded_aux_data.is_none() {
+        return Err(Error::new());
+    }
 
-    let mut private_key = HssPrivateKey::<H>::from(&rfc_private_key, &mut expanded_aux_data)
-        .map_err(|_| Error::new())?;
+    let mut private_key =
+        HssPrivateKey::<H>::from(&rfc_private_key, &mut expanded_aux_data, hash_operations)
+            .map_err(|_| Error::new())?;
 
-    let hss_signature = HssSignature::sign(
-        &mut private_key,
-        message,
-        message_mut,
-        &mut expanded_aux_data,
-    )
-    .map_err(|_| Error::new())?;
+    let result = use_private_key(&mut private_key, &mut expanded_aux_data, hash_operations)
+        .map_err(|_| Error::new())?;
 
     // Advance private key
     rfc_private_key.increment(&private_key);
     private_key_update_function(&rfc_private_key.to_binary_representation())
         .map_err(|_| Error::new())?;
 
-    let hash_iterations = {
-        let mut hash_iterations: u32 = 0;
-        for signed_public_key in hss_signature.signed_public_keys.iter() {
-            hash_iterations += signed_public_key.sig.lmots_signature.hash_iterations as u32;
-        }
-        hash_iterations + hss_signature.signature.lmots_signature.hash_iterations as u32
-    };
+    Ok(result)
+}
 
-    Signature::from_bytes_verbose(&hss_signature.to_binary_representation(), hash_iterations)
+/**
+ * Start the generation of a [`Signature`] for a message that is fed incrementally.
+ *
+ * The next leaf is reserved and the advanced private key is passed to
+ * `private_key_update_function` before the [`StreamSigner`] is returned. Hence, an abandoned
+ * [`StreamSigner`] only wastes a leaf but never leads to its reuse. This also applies to messages
+ * starting with `0^20 || D_PREHASH`, which [`StreamSigner::finalize`] refuses like [`hss_sign`].
+ *
+ * # Arguments
+ * * `HashChain` - The hasher implementation that should be used. ```Sha256``` is a standard software implementation.
+ * * `private_key` - The private key that should be used.
+ * * `private_key_update_function` - The update function that is called with the new private key. This function should save the new private key.
+ * * `aux_data` - Auxiliary data to speedup signature generation if available
+ */
+pub fn hss_begin_sign<H: HashChain>(
+    private_key: &[u8],
+    private_key_update_function: &mut dyn FnMut(&[u8]) -> Result<(), ()>,
+    aux_data: Option<&mut &mut [u8]>,
+) -> Result<StreamSigner<H>, Error> {
+    hss_advance_private_key::<H, _>(
+        private_key,
+        private_key_update_function,
+        aux_data,
+        false,
+        &mut HashOperations::default(),
+        StreamSigner::new,
+    )
 }
 
 /**
@@ -252,7 +1261,7 @@ fn hss_sign_core<H: HashChain>(
  *
  * * `HashChain` - The hasher implementation that should be used. ```Sha256``` is a standard software implementation.
  * * `parameters` - An array which specifies the Winternitz parameter and tree height of each individual HSS level. The first element describes Level 1, the second element Level 2 and so on.
- * * `seed` - An optional seed which will be used to generate the private key. It must be only used for testing purposes and not for production used key pairs.
+ * * `seed` - The seed which will be used to generate the private key. For production used key pairs, it must be filled from a cryptographically secure random number generator, e.g. by `hss_keygen_with_rng`.
  * * `aux_data` - The reference to a slice to auxiliary data. This can be used to speedup signature generation.
  *
  * # Example
@@ -278,195 +1287,991 @@ pub fn hss_keygen<H: HashChain>(
     parameters: &[HssParameter<H>],
     seed: &Seed<H>,
     aux_data: Option<&mut &mut [u8]>,
+) -> Result<(SigningKey<H>, VerifyingKey<H>), Error> {
+    hss_keygen_core(parameters, seed, aux_data, &mut HashOperations::default())
+}
+
+/**
+ * Generate [`SigningKey`] and [`VerifyingKey`] like [`hss_keygen`] from a seed filled by `rng`.
+ * Available with the `rand_core` feature, which doesn't require the std library.
+ *
+ * # Example
+ * ```
+ * use rand::rngs::OsRng;
+ * use hbs_lms::{keygen_with_rng, HssParameter, LmotsAlgorithm, LmsAlgorithm, Sha256_256};
+ *
+ * let parameters = [HssParameter::new(LmotsAlgorithm::LmotsW4, LmsAlgorithm::LmsH5)];
+ *
+ * let (signing_key, verifying_key) =
+ *      keygen_with_rng::<Sha256_256>(&parameters, &mut OsRng, None).unwrap();
+ * ```
+ */
+#[cfg(feature = "rand_core")]
+pub fn hss_keygen_with_rng<H: HashChain>(
+    parameters: &[HssParameter<H>],
+    rng: &mut (impl CryptoRng + RngCore),
+    aux_data: Option<&mut &mut [u8]>,
+) -> Result<(SigningKey<H>, VerifyingKey<H>), Error> {
+    let mut seed = Seed::default();
+    rng.try_fill_bytes(seed.as_mut_slice())
+        .map_err(|_| Error::new())?;
+
+    hss_keygen_core(parameters, &seed, aux_data, &mut HashOperations::default())
+}
+
+/**
+ * Generate [`SigningKey`] and [`VerifyingKey`] like [`hss_keygen`] and report the hash operations
+ * the key generation took.
+ */
+#[cfg(feature = "verbose")]
+pub fn hss_keygen_verbose<H: HashChain>(
+    parameters: &[HssParameter<H>],
+    seed: &Seed<H>,
+    aux_data: Option<&mut &mut [u8]>,
+) -> Result<(SigningKey<H>, VerifyingKey<H>, HashOperations), Error> {
+    let mut hash_operations = HashOperations::default();
+    let (signing_key, verifying_key) =
+        hss_keygen_core(parameters, seed, aux_data, &mut hash_operations)?;
+    Ok((signing_key, verifying_key, hash_operations))
+}
+
+fn hss_keygen_core<H: HashChain>(
+    parameters: &[HssParameter<H>],
+    seed: &Seed<H>,
+    aux_data: Option<&mut &mut [u8]>,
+    hash_operations: &mut HashOperations,
 ) -> Result<(SigningKey<H>, VerifyingKey<H>), Error> {
     let private_key =
         ReferenceImplPrivateKey::generate(parameters, seed).map_err(|_| Error::new())?;
 
-    let hss_public_key = HssPublicKey::from(&private_key, aux_data).map_err(|_| Error::new())?;
+    let hss_public_key =
+        HssPublicKey::from_parameters(&private_key, parameters, aux_data, hash_operations)
+            .map_err(|_| Error::new())?;
+
+    let signing_key = SigningKey::from_bytes(&private_key.to_binary_representation())?;
+    let verifying_key = VerifyingKey::from_bytes(&hss_public_key.to_binary_representation())?;
+    Ok((signing_key, verifying_key))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::util::helper::test_helper::gen_random_seed;
+    use crate::{
+        constants::{
+            lms_public_key_length, lms_signature_length, LMS_LEAF_IDENTIFIERS_SIZE, MAX_HASH_SIZE,
+            MAX_HSS_SIGNATURE_LENGTH, REF_IMPL_MAX_PRIVATE_KEY_SIZE,
+        },
+        hasher::{
+            sha256::{Sha256_128, Sha256_192, Sha256_256},
+            shake256::{Shake256_128, Shake256_192, Shake256_256},
+            HashChain,
+        },
+        hss::aux::{hss_aux_data_info_for_budget, hss_expand_aux_data, hss_finalize_aux_data},
+        LmotsAlgorithm, LmsAlgorithm,
+    };
+    use digest::Update;
+
+    use super::*;
+
+    #[test]
+    fn update_keypair() {
+        let message = [
+            32u8, 48, 2, 1, 48, 58, 20, 57, 9, 83, 99, 255, 0, 34, 2, 1, 0,
+        ];
+        type H = Sha256_256;
+        let seed = gen_random_seed::<H>();
+
+        let lmots = LmotsAlgorithm::LmotsW4;
+        let lms = LmsAlgorithm::LmsH5;
+        let parameters = [HssParameter::new(lmots, lms)];
+
+        let (mut signing_key, verifying_key) =
+            hss_keygen::<H>(&parameters, &seed, None).expect("Should generate HSS keys");
+
+        let signing_key_const = signing_key.duplicate();
+
+        let mut update_private_key = |new_key: &[u8]| {
+            signing_key.expose_secret_mut().copy_from_slice(new_key);
+            Ok(())
+        };
+
+        let signature = hss_sign::<H>(
+            &message,
+            signing_key_const.expose_secret(),
+            &mut update_private_key,
+            None,
+        )
+        .expect("Signing should complete without error.");
+
+        assert!(hss_verify::<H>(&message, signature.as_ref(), verifying_key.as_slice()).is_ok());
+
+        assert_ne!(
+            signing_key.expose_secret(),
+            signing_key_const.expose_secret()
+        );
+        assert_eq!(
+            signing_key.expose_secret()[LMS_LEAF_IDENTIFIERS_SIZE..],
+            signing_key_const.expose_secret()[LMS_LEAF_IDENTIFIERS_SIZE..]
+        );
+    }
+
+    #[test]
+    fn exhaust_keypair() {
+        let message = [
+            32u8, 48, 2, 1, 48, 58, 20, 57, 9, 83, 99, 255, 0, 34, 2, 1, 0,
+        ];
+        type H = Sha256_256;
+        let seed = gen_random_seed::<H>();
+
+        let lmots = LmotsAlgorithm::LmotsW2;
+        let lms = LmsAlgorithm::LmsH2;
+        let parameters = [HssParameter::new(lmots, lms), HssParameter::new(lmots, lms)];
+
+        let (mut signing_key, verifying_key) =
+            hss_keygen::<H>(&parameters, &seed, None).expect("Should generate HSS keys");
+        let keypair_lifetime = signing_key.get_lifetime().unwrap();
+
+        assert_ne!(
+            signing_key.expose_secret()
+                [(REF_IMPL_MAX_PRIVATE_KEY_SIZE - H::OUTPUT_SIZE as usize)..],
+            [0u8; H::OUTPUT_SIZE as usize],
+        );
+
+        for index in 0..keypair_lifetime {
+            assert_eq!(
+                signing_key.expose_secret()[..LMS_LEAF_IDENTIFIERS_SIZE],
+                index.to_be_bytes(),
+            );
+            assert_eq!(
+                keypair_lifetime - signing_key.get_lifetime().unwrap(),
+                index
+            );
+
+            let signing_key_const = signing_key.duplicate();
+
+            let mut update_private_key = |new_key: &[u8]| {
+                signing_key.expose_secret_mut().copy_from_slice(new_key);
+                Ok(())
+            };
+
+            let signature = hss_sign::<H>(
+                &message,
+                signing_key_const.expose_secret(),
+                &mut update_private_key,
+                None,
+            )
+            .expect("Signing should complete without error.");
+
+            assert!(
+                hss_verify::<H>(&message, signature.as_ref(), verifying_key.as_slice()).is_ok()
+            );
+        }
+        assert_eq!(
+            signing_key.expose_secret()
+                [(REF_IMPL_MAX_PRIVATE_KEY_SIZE - H::OUTPUT_SIZE as usize)..],
+            [0u8; H::OUTPUT_SIZE as usize],
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "Signing should panic!")]
+    fn use_exhausted_keypair() {
+        let message = [
+            32u8, 48, 2, 1, 48, 58, 20, 57, 9, 83, 99, 255, 0, 34, 2, 1, 0,
+        ];
+        type H = Sha256_256;
+        let seed = gen_random_seed::<H>();
+
+        let lmots = LmotsAlgorithm::LmotsW2;
+        let lms = LmsAlgorithm::LmsH2;
+        let parameters = [HssParameter::new(lmots, lms), HssParameter::new(lmots, lms)];
+
+        let (mut signing_key, verifying_key) =
+            hss_keygen::<H>(&parameters, &seed, None).expect("Should generate HSS keys");
+        let keypair_lifetime = signing_key.get_lifetime().unwrap();
+
+        for index in 0..(1u64 + keypair_lifetime) {
+            let signing_key_const = signing_key.duplicate();
+
+            let mut update_private_key = |new_key: &[u8]| {
+                signing_key.expose_secret_mut().copy_from_slice(new_key);
+                Ok(())
+            };
+
+            let signature = hss_sign::<H>(
+                &message,
+                signing_key_const.expose_secret(),
+                &mut update_private_key,
+                None,
+            )
+            .unwrap_or_else(|_| {
+                if index < keypair_lifetime {
+                    panic!("Signing should complete without error.");
+                } else {
+                    assert!(signing_key.get_lifetime().is_err());
+                    panic!("Signing should panic!");
+                }
+            });
+
+            assert!(
+                hss_verify::<H>(&message, signature.as_ref(), verifying_key.as_slice()).is_ok()
+            );
+        }
+    }
+
+    #[test]
+    fn keygen_with_forged_aux_data() {
+        type H = Sha256_256;
+        let seed = gen_random_seed::<H>();
+
+        let lmots = LmotsAlgorithm::LmotsW2;
+        let lms = LmsAlgorithm::LmsH5;
+        let parameters = [HssParameter::new(lmots, lms), HssParameter::new(lmots, lms)];
+
+        let mut aux_data = [0u8; 1_000];
+        let aux_slice: &mut &mut [u8] = &mut &mut aux_data[..];
+
+        let (sk1, vk1) =
+            hss_keygen::<H>(&parameters, &seed, Some(aux_slice)).expect("Should generate HSS keys");
+
+        aux_slice[2 * MAX_HASH_SIZE - 1] ^= 0x1;
+
+        let (sk2, vk2) =
+            hss_keygen::<H>(&parameters, &seed, Some(aux_slice)).expect("Should generate HSS keys");
+
+        assert_eq!(sk1, sk2);
+        assert_eq!(vk1, vk2);
+    }
+
+    #[cfg(feature = "verbose")]
+    #[test]
+    fn report_hash_operations() {
+        type H = Sha256_256;
+        let message = [1u8, 2, 3, 4, 5];
+        let seed = gen_random_seed::<H>();
+        let parameters = [HssParameter::new(
+            LmotsAlgorithm::LmotsW2,
+            LmsAlgorithm::LmsH5,
+        )];
+
+        // 32 leafs with 133 chains of 3 steps each, the LM-OTS public key spans 67 blocks
+        let (mut signing_key, verifying_key, keygen_operations) =
+            hss_keygen_verbose::<H>(&parameters, &seed, None).unwrap();
+        assert_eq!(
+            keygen_operations,
+            HashOperations {
+                chain: 32 * 133 * 3,
+                leaf: 32 * (67 + 1),
+                interior_node: 31 * 2,
+                seed_derive: 3 + 32 * 133,
+                message: 0,
+            }
+        );
+
+        let signature = signing_key.try_sign(&message).unwrap();
+        // I || q || D_MESG || C || message exceeds a single block
+        assert_eq!(signature.hash_operations.message, 2);
+        // The authentication path covers every leaf except the one used for signing
+        assert_eq!(
+            signature.hash_operations.chain,
+            31 * 133 * 3 + signature.hash_iterations as u64
+        );
+
+        let verify_operations =
+            hss_verify_verbose::<H>(&message, signature.as_ref(), verifying_key.as_slice())
+                .unwrap();
+        assert_eq!(
+            verify_operations,
+            HashOperations {
+                chain: 133 * 3 - signature.hash_iterations as u64,
+                leaf: 67 + 1,
+                interior_node: 5 * 2,
+                seed_derive: 0,
+                message: 2,
+            }
+        );
+        assert_eq!(verify_operations.total(), verify_operations.chain + 80);
+    }
+
+    #[test]
+    fn verify_batch_with_cache() {
+        type H = Sha256_256;
+        let seed = gen_random_seed::<H>();
+        let lmots = LmotsAlgorithm::LmotsW2;
+        let lms = LmsAlgorithm::LmsH2;
+        let parameters = [HssParameter::new(lmots, lms), HssParameter::new(lmots, lms)];
+
+        let (mut signing_key, verifying_key) =
+            hss_keygen::<H>(&parameters, &seed, None).expect("Should generate HSS keys");
+
+        let messages: [&[u8]; 6] = [b"0", b"1", b"2", b"3", b"4", b"5"];
+        let signatures = messages.map(|message| signing_key.try_sign(message).unwrap());
+        let batch: ArrayVec<[(&[u8], &[u8]); 6]> = messages
+            .iter()
+            .zip(signatures.iter())
+            .map(|(message, signature)| (*message, signature.as_ref()))
+            .collect();
+
+        // The six signatures span two subtrees with four leafs each
+        let mut cache = VerifierCache::<H, 4>::new();
+        assert!(verifying_key.verify_batch(&batch, &mut cache).is_ok());
+        assert_eq!(cache.len(), 2);
+
+        let mut forged_batch = batch;
+        forged_batch[3].0 = b"forged";
+        assert!(verifying_key
+            .verify_batch(&forged_batch, &mut cache)
+            .is_err());
+
+        // A child public key that does not match the cached one needs to be verified again
+        let child_public_key_end =
+            4 + lms_signature_length(MAX_HASH_SIZE, 133, 2) + lms_public_key_length(MAX_HASH_SIZE);
+        let mut forged_signature = ArrayVec::<[u8; MAX_HSS_SIGNATURE_LENGTH]>::new();
+        forged_signature.extend_from_slice(signatures[1].as_ref());
+        forged_signature[child_public_key_end - 1] ^= 1;
+        assert!(verifying_key
+            .verify_cached(messages[1], &forged_signature, &mut cache)
+            .is_err());
+        assert_eq!(cache.len(), 2);
+
+        let mut single_entry_cache = VerifierCache::<H, 1>::new();
+        assert!(verifying_key
+            .verify_batch(&batch, &mut single_entry_cache)
+            .is_ok());
+        assert_eq!(single_entry_cache.len(), 1);
+    }
+
+    #[test]
+    fn report_verification_cost() {
+        type H = Sha256_256;
+        let seed = gen_random_seed::<H>();
+        let lmots = LmotsAlgorithm::LmotsW2;
+        let lms = LmsAlgorithm::LmsH2;
+        let parameters = [HssParameter::new(lmots, lms), HssParameter::new(lmots, lms)];
+
+        let (mut signing_key, verifying_key) =
+            hss_keygen::<H>(&parameters, &seed, None).expect("Should generate HSS keys");
+
+        let message = b"message";
+        let signature = signing_key.try_sign(message).unwrap();
+
+        // A leaf and two interior nodes for each of the two levels
+        let cost = hss_verify_with_cost::<H>(message, signature.as_ref(), verifying_key.as_slice())
+            .unwrap();
+        assert_eq!(cost.chain_steps, signature.verify_chain_steps.unwrap());
+        assert_eq!(cost.tree_hashes, 2 * 3);
+
+        // The signed public key of the cached subtree is skipped
+        let mut cache = VerifierCache::<H, 1>::new();
+        let first_cost = hss_verify_cached_with_cost::<H, 1>(
+            message,
+            signature.as_ref(),
+            verifying_key.as_slice(),
+            &mut cache,
+        )
+        .unwrap();
+        let cached_cost = hss_verify_cached_with_cost::<H, 1>(
+            message,
+            signature.as_ref(),
+            verifying_key.as_slice(),
+            &mut cache,
+        )
+        .unwrap();
+        assert_eq!(first_cost, cost);
+        assert_eq!(cached_cost.tree_hashes, 3);
+        assert!(cached_cost.chain_steps < cost.chain_steps);
+
+        assert!(
+            hss_verify_with_cost::<H>(b"forged", signature.as_ref(), verifying_key.as_slice())
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn verify_streamed_message() {
+        type H = Sha256_256;
+        let seed = gen_random_seed::<H>();
+        let lmots = LmotsAlgorithm::LmotsW4;
+        let lms = LmsAlgorithm::LmsH5;
+        let parameters = [HssParameter::new(lmots, lms), HssParameter::new(lmots, lms)];
+
+        let (mut signing_key, verifying_key) =
+            hss_keygen::<H>(&parameters, &seed, None).expect("Should generate HSS keys");
+
+        let mut message = [0u8; 1000];
+        for (i, byte) in message.iter_mut().enumerate() {
+            *byte = i as u8;
+        }
+        let signature = signing_key.try_sign(&message).unwrap();
+
+        let mut verifier = verifying_key.begin_verify(&signature).unwrap();
+        for chunk in message.chunks(77) {
+            digest::Update::update(&mut verifier, chunk);
+        }
+        assert!(verifier.finalize().is_ok());
+
+        let mut verifier = verifying_key.begin_verify(&signature).unwrap();
+        digest::Update::update(&mut verifier, &message[..999]);
+        assert!(verifier.finalize().is_err());
+
+        #[cfg(feature = "std")]
+        {
+            let mut reader = std::io::Cursor::new(&message[..]);
+            assert!(verifying_key.verify_reader(&mut reader, &signature).is_ok());
+
+            message[500] ^= 1;
+            let mut reader = std::io::Cursor::new(&message[..]);
+            assert!(verifying_key
+                .verify_reader(&mut reader, &signature)
+                .is_err());
+        }
+    }
+
+    #[test]
+    fn sign_streamed_message() {
+        type H = Sha256_256;
+        let seed = gen_random_seed::<H>();
+        let lmots = LmotsAlgorithm::LmotsW4;
+        let lms = LmsAlgorithm::LmsH5;
+        let parameters = [HssParameter::new(lmots, lms), HssParameter::new(lmots, lms)];
+
+        let (mut signing_key, verifying_key) =
+            hss_keygen::<H>(&parameters, &seed, None).expect("Should generate HSS keys");
+        let mut reference_signing_key = signing_key.duplicate();
 
-    let signing_key = SigningKey::from_bytes(&private_key.to_binary_representation())?;
-    let verifying_key = VerifyingKey::from_bytes(&hss_public_key.to_binary_representation())?;
-    Ok((signing_key, verifying_key))
-}
+        let mut message = [0u8; 1000];
+        for (i, byte) in message.iter_mut().enumerate() {
+            *byte = i as u8;
+        }
 
-#[cfg(test)]
-mod tests {
-    use crate::util::helper::test_helper::gen_random_seed;
-    use crate::{
-        constants::{LMS_LEAF_IDENTIFIERS_SIZE, MAX_HASH_SIZE},
-        hasher::{
-            sha256::{Sha256_128, Sha256_192, Sha256_256},
-            shake256::{Shake256_128, Shake256_192, Shake256_256},
-            HashChain,
-        },
-        LmotsAlgorithm, LmsAlgorithm,
-    };
+        let lifetime = signing_key.get_lifetime().unwrap();
+        let mut signer = signing_key.begin_sign().unwrap();
+        assert_eq!(signing_key.get_lifetime().unwrap(), lifetime - 1);
 
-    use super::*;
+        for chunk in message.chunks(77) {
+            digest::Update::update(&mut signer, chunk);
+        }
+        let signature = signer.finalize().unwrap();
+
+        assert!(verifying_key.verify(&message, &signature).is_ok());
+        assert_eq!(
+            signature.as_ref(),
+            reference_signing_key.try_sign(&message).unwrap().as_ref()
+        );
+
+        // An abandoned signer has still used up its leaf
+        drop(signing_key.begin_sign().unwrap());
+        reference_signing_key.try_sign(&message).unwrap();
+        assert_eq!(signing_key, reference_signing_key);
+    }
 
     #[test]
-    fn update_keypair() {
-        let message = [
-            32u8, 48, 2, 1, 48, 58, 20, 57, 9, 83, 99, 255, 0, 34, 2, 1, 0,
-        ];
+    fn sign_and_verify_digest() {
         type H = Sha256_256;
         let seed = gen_random_seed::<H>();
-
         let lmots = LmotsAlgorithm::LmotsW4;
         let lms = LmsAlgorithm::LmsH5;
-        let parameters = [HssParameter::new(lmots, lms)];
+        let parameters = [HssParameter::new(lmots, lms), HssParameter::new(lmots, lms)];
 
         let (mut signing_key, verifying_key) =
             hss_keygen::<H>(&parameters, &seed, None).expect("Should generate HSS keys");
 
-        let signing_key_const = signing_key.clone();
+        let message = [42u8; 300];
+        let digest = H::default().chain(&message[..100]).chain(&message[100..]);
+        let digest_output = HashChain::finalize(digest.clone());
+
+        let signature = signing_key.try_sign_digest(digest.clone()).unwrap();
+        assert!(verifying_key
+            .verify_digest(digest.clone(), &signature)
+            .is_ok());
+        assert!(verifying_key
+            .verify_digest(H::default().chain(&message[1..]), &signature)
+            .is_err());
+
+        // The prehash mode is separated from signing the message or the digest as message
+        assert!(verifying_key.verify(&digest_output, &signature).is_err());
+        assert!(verifying_key.verify(&message, &signature).is_err());
+        let signature = signing_key.try_sign(&digest_output).unwrap();
+        assert!(verifying_key
+            .verify_digest(digest.clone(), &signature)
+            .is_err());
+
+        let keypair = SigningKeypair::new(signing_key.duplicate(), verifying_key.clone());
+        let signature = keypair.try_sign_digest(digest.clone()).unwrap();
+        assert!(keypair
+            .verifying_key()
+            .verify_digest(digest, &signature)
+            .is_ok());
+        let signature = keypair.try_sign(&message).unwrap();
+        assert!(keypair.verifying_key().verify(&message, &signature).is_ok());
 
-        let mut update_private_key = |new_key: &[u8]| {
-            signing_key.as_mut_slice().copy_from_slice(new_key);
-            Ok(())
-        };
+        assert_eq!(
+            keypair.signing_key().get_lifetime().unwrap(),
+            signing_key.get_lifetime().unwrap() - 2
+        );
+    }
 
-        let signature = hss_sign::<H>(
+    #[test]
+    fn plain_signing_refuses_prehash_messages() {
+        type H = Sha256_256;
+        let seed = gen_random_seed::<H>();
+        let lmots = LmotsAlgorithm::LmotsW4;
+        let lms = LmsAlgorithm::LmsH5;
+        let parameters = [HssParameter::new(lmots, lms), HssParameter::new(lmots, lms)];
+
+        let (mut signing_key, verifying_key) =
+            hss_keygen::<H>(&parameters, &seed, None).expect("Should generate HSS keys");
+        let unchanged_signing_key = signing_key.duplicate();
+
+        let digest = H::default().chain([42u8; 300]);
+        let message = prehash_message(digest.clone());
+
+        assert!(signing_key.try_sign(&message).is_err());
+        assert!(signing_key
+            .try_sign_with_required_aux(&message, &mut &mut [0u8; 1_000][..])
+            .is_err());
+        assert!(hss_sign::<H>(
             &message,
-            signing_key_const.as_slice(),
-            &mut update_private_key,
-            None,
+            unchanged_signing_key.expose_secret(),
+            &mut |_| Ok(()),
+            None
         )
-        .expect("Signing should complete without error.");
+        .is_err());
+        assert_eq!(signing_key, unchanged_signing_key);
+
+        // The streamed message is only known after the leaf is reserved
+        let mut signer = signing_key.begin_sign().unwrap();
+        signer.update(&message[..PREHASH_D]);
+        signer.update(&message[PREHASH_D..]);
+        assert!(signer.finalize().is_err());
+
+        let (mut delegating_key, _) = hss_keygen::<H>(&parameters, &seed, None).unwrap();
+        let mut delegated_key = delegating_key.delegate(0, None).unwrap();
+        assert!(delegated_key.try_sign(&message).is_err());
+
+        // The prefix alone doesn't matter elsewhere in the message
+        let mut shifted_message = [0u8; PREHASH_PREFIX_LEN + MAX_HASH_SIZE + 1];
+        shifted_message[1..].copy_from_slice(&message);
+        let signature = signing_key.try_sign(&shifted_message).unwrap();
+        assert!(verifying_key.verify(&shifted_message, &signature).is_ok());
+        assert!(verifying_key.verify_digest(digest, &signature).is_err());
+    }
 
-        assert!(hss_verify::<H>(&message, signature.as_ref(), verifying_key.as_slice()).is_ok());
+    #[test]
+    fn recompute_verifying_key() {
+        type H = Sha256_256;
+        let seed = gen_random_seed::<H>();
+        let lmots = LmotsAlgorithm::LmotsW4;
+        let lms = LmsAlgorithm::LmsH5;
+        let parameters = [HssParameter::new(lmots, lms), HssParameter::new(lmots, lms)];
 
-        assert_ne!(signing_key.as_slice(), signing_key_const.as_slice());
+        let mut aux_data = [0u8; 1_000];
+        let aux_slice: &mut &mut [u8] = &mut &mut aux_data[..];
+
+        let (mut signing_key, verifying_key) =
+            hss_keygen::<H>(&parameters, &seed, Some(aux_slice)).expect("Should generate HSS keys");
+        signing_key.try_sign(&[1u8, 2, 3]).unwrap();
+
+        assert_eq!(
+            signing_key.verifying_key(None, None).unwrap(),
+            verifying_key
+        );
+        let aux_slice: &mut &mut [u8] = &mut &mut aux_data[..];
         assert_eq!(
-            signing_key.as_slice()[LMS_LEAF_IDENTIFIERS_SIZE..],
-            signing_key_const.as_slice()[LMS_LEAF_IDENTIFIERS_SIZE..]
+            signing_key
+                .verifying_key(Some(aux_slice), Some(&verifying_key))
+                .unwrap(),
+            verifying_key
         );
+
+        let (_, other_verifying_key) =
+            hss_keygen::<H>(&parameters, &gen_random_seed::<H>(), None).unwrap();
+        assert!(signing_key
+            .verifying_key(None, Some(&other_verifying_key))
+            .is_err());
     }
 
     #[test]
-    fn exhaust_keypair() {
-        let message = [
-            32u8, 48, 2, 1, 48, 58, 20, 57, 9, 83, 99, 255, 0, 34, 2, 1, 0,
-        ];
+    fn sign_with_shards() {
         type H = Sha256_256;
         let seed = gen_random_seed::<H>();
-
-        let lmots = LmotsAlgorithm::LmotsW2;
-        let lms = LmsAlgorithm::LmsH2;
+        let lmots = LmotsAlgorithm::LmotsW4;
+        let lms = LmsAlgorithm::LmsH5;
         let parameters = [HssParameter::new(lmots, lms), HssParameter::new(lmots, lms)];
+        let message = [1u8, 2, 3];
 
         let (mut signing_key, verifying_key) =
             hss_keygen::<H>(&parameters, &seed, None).expect("Should generate HSS keys");
-        let keypair_lifetime = signing_key.get_lifetime().unwrap();
-
-        assert_ne!(
-            signing_key.as_slice()[(REF_IMPL_MAX_PRIVATE_KEY_SIZE - H::OUTPUT_SIZE as usize)..],
-            [0u8; H::OUTPUT_SIZE as usize],
-        );
+        signing_key.try_sign(&message).unwrap();
+        assert_eq!(signing_key.signature_range().unwrap(), 1..1024);
+
+        let mut shards = signing_key.into_shards(2).unwrap();
+        let mut first_shard = shards.next().unwrap();
+        let second_shard = shards.next().unwrap();
+        assert!(shards.next().is_none());
+        assert_eq!(first_shard.signature_range().unwrap(), 1..512);
+        assert_eq!(second_shard.signature_range().unwrap(), 512..1024);
+
+        // The second shard signs the top leaf of the first one, which is then exhausted
+        let mut shards = second_shard.into_shards(512).unwrap();
+        let mut last_shard = shards.next().unwrap();
+        assert_eq!(last_shard.get_lifetime().unwrap(), 1);
+
+        for shard in [&mut first_shard, &mut last_shard] {
+            let signature = shard.try_sign(&message).unwrap();
+            assert!(verifying_key.verify(&message, &signature).is_ok());
+        }
+        assert_eq!(first_shard.get_lifetime().unwrap(), 510);
+        assert!(last_shard.try_sign(&message).is_err());
 
-        for index in 0..keypair_lifetime {
-            assert_eq!(
-                signing_key.as_slice()[..LMS_LEAF_IDENTIFIERS_SIZE],
-                index.to_be_bytes(),
-            );
-            assert_eq!(
-                keypair_lifetime - signing_key.get_lifetime().unwrap(),
-                index
-            );
+        let (signing_key, _) = hss_keygen::<H>(&parameters, &seed, None).unwrap();
+        assert!(signing_key.into_shards(1025).is_err());
+    }
 
-            let signing_key_const = signing_key.clone();
+    #[test]
+    fn delegate_child_tree() {
+        type H = Sha256_256;
+        let seed = gen_random_seed::<H>();
+        let lmots = LmotsAlgorithm::LmotsW4;
+        let lms = LmsAlgorithm::LmsH5;
+        let parameters = [
+            HssParameter::new(lmots, lms),
+            HssParameter::new(lmots, lms).with_fast_verify_signed_public_key(),
+        ];
+        let message = [1u8, 2, 3];
 
-            let mut update_private_key = |new_key: &[u8]| {
-                signing_key.as_mut_slice().copy_from_slice(new_key);
-                Ok(())
-            };
+        let mut aux_data = [0u8; 1_000];
+        let aux_slice: &mut &mut [u8] = &mut &mut aux_data[..];
 
-            let signature = hss_sign::<H>(
-                &message,
-                signing_key_const.as_slice(),
-                &mut update_private_key,
-                None,
-            )
-            .expect("Signing should complete without error.");
+        let (mut signing_key, verifying_key) =
+            hss_keygen::<H>(&parameters, &seed, Some(aux_slice)).expect("Should generate HSS keys");
 
-            assert!(
-                hss_verify::<H>(&message, signature.as_ref(), verifying_key.as_slice()).is_ok()
-            );
+        // Only the tree at the next signature index can be delegated
+        assert!(signing_key.duplicate().delegate(1, None).is_err());
+        assert!(signing_key.duplicate().delegate(32, None).is_err());
+
+        let lifetime = signing_key.get_lifetime().unwrap();
+        let mut delegated_key = signing_key.delegate(0, Some(aux_slice)).unwrap();
+        assert_eq!(signing_key.get_lifetime().unwrap(), lifetime - 32);
+        assert_eq!(signing_key.signature_range().unwrap(), 32..1024);
+        assert!(signing_key.duplicate().delegate(0, None).is_err());
+        assert_eq!(delegated_key.get_lifetime().unwrap(), 32);
+
+        for _ in 0..2 {
+            let signature = delegated_key.try_sign(&message).unwrap();
+            assert!(verifying_key.verify(&message, &signature).is_ok());
+
+            let cost =
+                hss_verify_with_cost::<H>(&message, signature.as_ref(), verifying_key.as_slice())
+                    .unwrap();
+            assert_eq!(signature.verify_chain_steps, Some(cost.chain_steps));
         }
-        assert_eq!(
-            signing_key.as_slice()[(REF_IMPL_MAX_PRIVATE_KEY_SIZE - H::OUTPUT_SIZE as usize)..],
-            [0u8; H::OUTPUT_SIZE as usize],
+
+        let mut delegated_key =
+            DelegatedSigningKey::<H>::from_bytes(delegated_key.expose_secret()).unwrap();
+        assert_eq!(delegated_key.get_lifetime().unwrap(), 30);
+        let signature = delegated_key.try_sign(&message).unwrap();
+        assert!(verifying_key.verify(&message, &signature).is_ok());
+
+        // The parent key continues with the tree after the delegated one
+        let signature = signing_key.try_sign(&message).unwrap();
+        assert!(verifying_key.verify(&message, &signature).is_ok());
+        assert_eq!(signature.as_ref()[4..8], 1u32.to_be_bytes());
+
+        // The rest of the partially used tree would be skipped
+        assert!(signing_key.duplicate().delegate(1, None).is_err());
+        assert!(signing_key.duplicate().delegate(2, None).is_err());
+
+        assert!(
+            DelegatedSigningKey::<H>::from_bytes(&delegated_key.expose_secret()[1..])
+                .unwrap()
+                .try_sign(&message)
+                .is_err()
         );
+
+        let (mut single_tree_key, _) = hss_keygen::<H>(&parameters[..1], &seed, None).unwrap();
+        assert!(single_tree_key.delegate(0, None).is_err());
     }
 
     #[test]
-    #[should_panic(expected = "Signing should panic!")]
-    fn use_exhausted_keypair() {
-        let message = [
-            32u8, 48, 2, 1, 48, 58, 20, 57, 9, 83, 99, 255, 0, 34, 2, 1, 0,
+    fn delegated_key_signs_with_aux_data() {
+        type H = Sha256_256;
+        let seed = gen_random_seed::<H>();
+        let parameters = [
+            HssParameter::new(LmotsAlgorithm::LmotsW4, LmsAlgorithm::LmsH5),
+            HssParameter::new(LmotsAlgorithm::LmotsW4, LmsAlgorithm::LmsH5),
         ];
+        let message = [1u8, 2, 3];
+
+        let (mut signing_key, verifying_key) = hss_keygen::<H>(&parameters, &seed, None).unwrap();
+        let mut delegated_key = signing_key.delegate(0, None).unwrap();
+
+        let mut aux_data = [0u8; 1_000];
+        let aux_slice: &mut &mut [u8] = &mut &mut aux_data[..];
+
+        // The first signature fills the aux data with the delegated tree
+        let signature = delegated_key
+            .try_sign_with_aux(&message, Some(aux_slice))
+            .unwrap();
+        assert!(verifying_key.verify(&message, &signature).is_ok());
+        assert!(hss_is_aux_data_used(aux_slice));
+        let filled_aux_data = aux_slice.to_vec();
+
+        let signature = delegated_key
+            .try_sign_with_aux(&message, Some(aux_slice))
+            .unwrap();
+        assert!(verifying_key.verify(&message, &signature).is_ok());
+        assert_eq!(aux_slice.to_vec(), filled_aux_data);
+        assert_eq!(delegated_key.get_lifetime().unwrap(), 30);
+
+        // The nodes are taken from the aux data instead of being regenerated
+        let delegated_private_key =
+            DelegatedPrivateKey::<H>::from_binary_representation(delegated_key.expose_secret())
+                .unwrap();
+        let delegated_seed = delegated_private_key.seed.seed.as_slice();
+        let mut forged_aux_data = filled_aux_data.clone();
+        {
+            let mut expanded_aux_data =
+                hss_expand_aux_data::<H>(Some(&mut forged_aux_data[..]), Some(delegated_seed))
+                    .unwrap();
+            for level in expanded_aux_data.data.iter_mut().skip(1).flatten() {
+                level.iter_mut().for_each(|byte| *byte ^= 1);
+            }
+            hss_finalize_aux_data::<H>(&mut expanded_aux_data, delegated_seed);
+        }
+        let mut forged_key =
+            DelegatedSigningKey::<H>::from_bytes(delegated_key.expose_secret()).unwrap();
+        let signature = forged_key
+            .try_sign_with_aux(&message, Some(&mut &mut forged_aux_data[..]))
+            .unwrap();
+        assert!(verifying_key.verify(&message, &signature).is_err());
+
+        // The aux data of the parent key isn't authentic for the delegated key
+        let mut parent_aux_data = [0u8; 1_000];
+        hss_keygen::<H>(&parameters, &seed, Some(&mut &mut parent_aux_data[..])).unwrap();
+        let unchanged_parent_aux_data = parent_aux_data;
+        let signature = delegated_key
+            .try_sign_with_aux(&message, Some(&mut &mut parent_aux_data[..]))
+            .unwrap();
+        assert!(verifying_key.verify(&message, &signature).is_ok());
+        assert_eq!(parent_aux_data, unchanged_parent_aux_data);
+    }
+
+    #[test]
+    fn split_backup_key() {
         type H = Sha256_256;
         let seed = gen_random_seed::<H>();
+        let parameters = [
+            HssParameter::new(LmotsAlgorithm::LmotsW4, LmsAlgorithm::LmsH5),
+            HssParameter::new(LmotsAlgorithm::LmotsW4, LmsAlgorithm::LmsH5),
+        ];
+        let message = [1u8, 2, 3];
+
+        let (mut signing_key, verifying_key) = hss_keygen::<H>(&parameters, &seed, None).unwrap();
+        signing_key.try_sign(&message).unwrap();
+
+        assert!(signing_key.duplicate().split_backup(0, 0).is_err());
+        assert!(signing_key.duplicate().split_backup(1023, 0).is_err());
+
+        let backup = signing_key.split_backup(256, 1_700_000_000).unwrap();
+        assert_eq!(signing_key.signature_range().unwrap(), 1..768);
+        assert_eq!(signing_key.get_lifetime().unwrap(), 767);
+        assert_eq!(backup.backup_range().unwrap(), 768..1024);
+        assert_eq!(backup.created_at, 1_700_000_000);
+
+        let mut restored = BackupKey::<H>::from_bytes(&backup.to_bytes()).unwrap();
+        assert_eq!(restored, backup);
+        let signature = restored.signing_key.try_sign(&message).unwrap();
+        assert!(verifying_key.verify(&message, &signature).is_ok());
+        assert_eq!(signature.as_ref()[4..8], 24u32.to_be_bytes());
+        assert_eq!(restored.backup_range().unwrap(), 768..1024);
+
+        let signature = signing_key.try_sign(&message).unwrap();
+        assert!(verifying_key.verify(&message, &signature).is_ok());
+
+        assert!(BackupKey::<H>::from_bytes(&backup.to_bytes()[..7]).is_err());
+        let mut unsharded_backup = ArrayVec::<[u8; MAX_BACKUP_KEY_SIZE]>::new();
+        unsharded_backup.extend_from_slice(&[0u8; 8]);
+        unsharded_backup.extend_from_slice(
+            hss_keygen::<H>(&parameters, &seed, None)
+                .unwrap()
+                .0
+                .expose_secret(),
+        );
+        assert!(BackupKey::<H>::from_bytes(&unsharded_backup).is_err());
+    }
 
-        let lmots = LmotsAlgorithm::LmotsW2;
-        let lms = LmsAlgorithm::LmsH2;
-        let parameters = [HssParameter::new(lmots, lms), HssParameter::new(lmots, lms)];
+    #[cfg(feature = "rand_core")]
+    #[test]
+    fn keygen_with_rng() {
+        use rand::{rngs::StdRng, RngCore, SeedableRng};
+
+        type H = Sha256_256;
+        let parameters = [HssParameter::new(
+            LmotsAlgorithm::LmotsW4,
+            LmsAlgorithm::LmsH5,
+        )];
+        let message = [1u8, 2, 3];
 
         let (mut signing_key, verifying_key) =
-            hss_keygen::<H>(&parameters, &seed, None).expect("Should generate HSS keys");
-        let keypair_lifetime = signing_key.get_lifetime().unwrap();
+            hss_keygen_with_rng::<H>(&parameters, &mut StdRng::seed_from_u64(42), None).unwrap();
+        let signature = signing_key.try_sign(&message).unwrap();
+        assert!(verifying_key.verify(&message, &signature).is_ok());
+
+        // Same keys as from a seed filled by the same random number generator
+        let mut seed = Seed::default();
+        StdRng::seed_from_u64(42).fill_bytes(seed.as_mut_slice());
+        let (_, seeded_verifying_key) = hss_keygen::<H>(&parameters, &seed, None).unwrap();
+        assert_eq!(verifying_key, seeded_verifying_key);
+
+        let (_, other_verifying_key) =
+            hss_keygen_with_rng::<H>(&parameters, &mut StdRng::seed_from_u64(43), None).unwrap();
+        assert_ne!(verifying_key, other_verifying_key);
+    }
 
-        for index in 0..(1u64 + keypair_lifetime) {
-            let signing_key_const = signing_key.clone();
+    #[cfg(feature = "std")]
+    #[test]
+    fn signing_key_secret_hygiene() {
+        type H = Sha256_256;
+        let seed = gen_random_seed::<H>();
+        let parameters = [HssParameter::new(
+            LmotsAlgorithm::LmotsW4,
+            LmsAlgorithm::LmsH5,
+        )];
 
-            let mut update_private_key = |new_key: &[u8]| {
-                signing_key.as_mut_slice().copy_from_slice(new_key);
-                Ok(())
-            };
+        let (mut signing_key, _) = hss_keygen::<H>(&parameters, &seed, None).unwrap();
+        assert_eq!(std::format!("{:?}", signing_key), "SigningKey { .. }");
 
-            let signature = hss_sign::<H>(
-                &message,
-                signing_key_const.as_slice(),
-                &mut update_private_key,
-                None,
-            )
-            .unwrap_or_else(|_| {
-                if index < keypair_lifetime {
-                    panic!("Signing should complete without error.");
-                } else {
-                    assert!(signing_key.get_lifetime().is_err());
-                    panic!("Signing should panic!");
-                }
-            });
+        let duplicate = signing_key.duplicate();
+        assert_eq!(signing_key, duplicate);
+        signing_key.try_sign(&[1u8, 2, 3]).unwrap();
+        assert_ne!(signing_key, duplicate);
 
-            assert!(
-                hss_verify::<H>(&message, signature.as_ref(), verifying_key.as_slice()).is_ok()
-            );
-        }
+        signing_key.zeroize();
+        assert!(signing_key.expose_secret().iter().all(|&byte| byte == 0));
     }
 
     #[test]
-    fn keygen_with_forged_aux_data() {
+    fn validate_and_regenerate_aux() {
         type H = Sha256_256;
         let seed = gen_random_seed::<H>();
-
-        let lmots = LmotsAlgorithm::LmotsW2;
+        let lmots = LmotsAlgorithm::LmotsW4;
         let lms = LmsAlgorithm::LmsH5;
         let parameters = [HssParameter::new(lmots, lms), HssParameter::new(lmots, lms)];
+        let message = [1u8, 2, 3];
 
         let mut aux_data = [0u8; 1_000];
         let aux_slice: &mut &mut [u8] = &mut &mut aux_data[..];
 
-        let (sk1, vk1) =
+        let (mut signing_key, verifying_key) =
             hss_keygen::<H>(&parameters, &seed, Some(aux_slice)).expect("Should generate HSS keys");
+        let info = signing_key.validate_aux(aux_slice).unwrap();
+        assert_eq!(info.size(), aux_slice.len());
 
-        aux_slice[2 * MAX_HASH_SIZE - 1] ^= 0x1;
+        let aux_len = aux_slice.len();
+        assert_eq!(
+            signing_key.validate_aux(&aux_slice[..aux_len - 1]),
+            Err(AuxDataError::Malformed)
+        );
+        assert_eq!(
+            signing_key.validate_aux(&[0u8; 1_000]),
+            Err(AuxDataError::Unused)
+        );
+        let (other_signing_key, _) =
+            hss_keygen::<H>(&parameters, &gen_random_seed::<H>(), None).unwrap();
+        assert_eq!(
+            other_signing_key.validate_aux(aux_slice),
+            Err(AuxDataError::NotAuthentic)
+        );
 
-        let (sk2, vk2) =
-            hss_keygen::<H>(&parameters, &seed, Some(aux_slice)).expect("Should generate HSS keys");
+        aux_slice[2 * MAX_HASH_SIZE - 1] ^= 1;
+        assert_eq!(
+            signing_key.validate_aux(aux_slice),
+            Err(AuxDataError::NotAuthentic)
+        );
 
-        assert_eq!(sk1, sk2);
-        assert_eq!(vk1, vk2);
+        // No leaf is reserved if the required aux data is corrupted
+        let unchanged_signing_key = signing_key.duplicate();
+        assert!(signing_key
+            .try_sign_with_required_aux(&message, aux_slice)
+            .is_err());
+        assert_eq!(signing_key, unchanged_signing_key);
+
+        let mut fresh_aux_data = [0u8; 1_000];
+        assert!(signing_key
+            .try_sign_with_required_aux(&message, &mut &mut fresh_aux_data[..])
+            .is_err());
+        assert!(fresh_aux_data.iter().all(|&byte| byte == 0));
+        assert_eq!(signing_key, unchanged_signing_key);
+
+        let aux_slice: &mut &mut [u8] = &mut &mut aux_data[..];
+        assert_eq!(signing_key.regenerate_aux(aux_slice).unwrap(), info);
+        assert_eq!(signing_key.validate_aux(aux_slice).unwrap(), info);
+
+        let signature = signing_key
+            .try_sign_with_required_aux(&message, aux_slice)
+            .unwrap();
+        assert!(verifying_key.verify(&message, &signature).is_ok());
+        assert_eq!(signing_key.validate_aux(aux_slice).unwrap(), info);
+    }
+
+    #[test]
+    fn aux_data_without_lower_levels_stays_unchanged() {
+        type H = Sha256_256;
+        let seed = gen_random_seed::<H>();
+        let lmots = LmotsAlgorithm::LmotsW2;
+        let parameters = [
+            HssParameter::new(lmots, LmsAlgorithm::LmsH5),
+            HssParameter::new(lmots, LmsAlgorithm::LmsH2),
+        ];
+        let message = [1u8, 2, 3];
+
+        // Without the option, the aux data only caches the top tree and ends with its MAC like the
+        // aux data of the reference implementation, which older versions of this crate require
+        let mut aux_data = [0u8; 10_000];
+        let aux_slice: &mut &mut [u8] = &mut &mut aux_data[..];
+        let (mut signing_key, verifying_key) =
+            hss_keygen::<H>(&parameters, &seed, Some(aux_slice)).unwrap();
+        let info = signing_key.validate_aux(aux_slice).unwrap();
+        assert_eq!(info.lower_tree_levels(1).count(), 0);
+        assert_eq!(
+            info,
+            hss_aux_data_info_for_budget(&parameters[..1], aux_slice.len())
+        );
+        let aux_len = aux_slice.len();
+
+        // Such aux data in a larger buffer is neither extended nor changed by signing, also when
+        // the tree of the lower level is replaced
+        let mut reference_aux_data = [0xa5u8; 10_000];
+        reference_aux_data[..aux_len].copy_from_slice(&aux_data[..aux_len]);
+        let unchanged_aux_data = reference_aux_data;
+        assert_eq!(signing_key.validate_aux(&reference_aux_data).unwrap(), info);
+
+        for _ in 0..6 {
+            let signature = signing_key
+                .try_sign_with_required_aux(&message, &mut &mut reference_aux_data[..])
+                .unwrap();
+            assert!(verifying_key.verify(&message, &signature).is_ok());
+            assert!(reference_aux_data == unchanged_aux_data);
+        }
+
+        // With the option, the lower level is cached behind the unchanged aux data of the top tree
+        let parameters = [parameters[0], parameters[1].with_aux_data_cache()];
+        let mut cached_aux_data = [0u8; 10_000];
+        let aux_slice: &mut &mut [u8] = &mut &mut cached_aux_data[..];
+        let (mut signing_key, verifying_key) =
+            hss_keygen::<H>(&parameters, &seed, Some(aux_slice)).unwrap();
+        let cached_info = signing_key.validate_aux(aux_slice).unwrap();
+        assert!(cached_info.lower_tree_levels(1).count() > 0);
+        assert_eq!(&aux_slice[..aux_len], &aux_data[..aux_len]);
+
+        for _ in 0..6 {
+            let signature = signing_key
+                .try_sign_with_required_aux(&message, aux_slice)
+                .unwrap();
+            assert!(verifying_key.verify(&message, &signature).is_ok());
+            assert_eq!(&aux_slice[..aux_len], &aux_data[..aux_len]);
+        }
     }
 
     #[test]
@@ -518,16 +2323,16 @@ mod tests {
         let mut message = [0u8; 64];
         message[..message_values.len()].copy_from_slice(&message_values);
 
-        let signing_key_const = signing_key.clone();
+        let signing_key_const = signing_key.duplicate();
 
         let mut update_private_key = |new_key: &[u8]| {
-            signing_key.as_mut_slice().copy_from_slice(new_key);
+            signing_key.expose_secret_mut().copy_from_slice(new_key);
             Ok(())
         };
 
         let signature = hss_sign::<H>(
             &message,
-            signing_key_const.as_slice(),
+            signing_key_const.expose_secret(),
             &mut update_private_key,
             None,
         )
@@ -563,18 +2368,19 @@ mod tests {
         let mut message = [0u8; 64];
         message[..message_values.len()].copy_from_slice(&message_values);
 
-        let signing_key_const = signing_key.clone();
+        let signing_key_const = signing_key.duplicate();
 
         let mut update_private_key = |new_key: &[u8]| {
-            signing_key.as_mut_slice().copy_from_slice(new_key);
+            signing_key.expose_secret_mut().copy_from_slice(new_key);
             Ok(())
         };
 
         let signature = hss_sign_mut::<H>(
             &mut message,
-            signing_key_const.as_slice(),
+            signing_key_const.expose_secret(),
             &mut update_private_key,
             None,
+            &FastVerifyOptions::default(),
         )
         .expect("Signing should complete without error.");
 
@@ -586,4 +2392,132 @@ mod tests {
 
         assert!(hss_verify::<H>(&message, signature.as_ref(), verifying_key.as_slice()).is_ok());
     }
+
+    #[cfg(feature = "fast_verify")]
+    #[test]
+    fn fast_verify_options() {
+        type H = Sha256_256;
+        let seed = gen_random_seed::<H>();
+
+        let (mut signing_key, verifying_key) =
+            hss_keygen::<H>(&[HssParameter::construct_default_parameters()], &seed, None)
+                .expect("Should generate HSS keys");
+
+        let mut sign_mut = |message: &mut [u8], fast_verify_options: &FastVerifyOptions| {
+            let signing_key_const = signing_key.duplicate();
+            hss_sign_mut::<H>(
+                message,
+                signing_key_const.expose_secret(),
+                &mut |new_key: &[u8]| {
+                    signing_key.expose_secret_mut().copy_from_slice(new_key);
+                    Ok(())
+                },
+                None,
+                fast_verify_options,
+            )
+            .expect("Signing should complete without error.")
+        };
+
+        let mut message = [42u8; 64];
+        message[64 - MAX_HASH_SIZE..].fill(0);
+        let signature = sign_mut(
+            &mut message,
+            &FastVerifyOptions::default()
+                .with_max_hash_optimizations(30)
+                .with_threads(3),
+        );
+        assert_ne!(message[64 - MAX_HASH_SIZE..], [0u8; MAX_HASH_SIZE]);
+        assert!(hss_verify::<H>(&message, signature.as_ref(), verifying_key.as_slice()).is_ok());
+
+        // More threads than trials
+        let mut message = [42u8; 64];
+        message[64 - MAX_HASH_SIZE..].fill(0);
+        let signature = sign_mut(
+            &mut message,
+            &FastVerifyOptions::default()
+                .with_max_hash_optimizations(2)
+                .with_threads(8),
+        );
+        assert_ne!(message[64 - MAX_HASH_SIZE..], [0u8; MAX_HASH_SIZE]);
+        assert!(hss_verify::<H>(&message, signature.as_ref(), verifying_key.as_slice()).is_ok());
+
+        // No randomizer is tried after the deadline, the message is signed as it is
+        let mut message = [42u8; 64];
+        message[64 - MAX_HASH_SIZE..].fill(0);
+        let signature = sign_mut(
+            &mut message,
+            &FastVerifyOptions::default().with_deadline(std::time::Instant::now()),
+        );
+        assert_eq!(message[64 - MAX_HASH_SIZE..], [0u8; MAX_HASH_SIZE]);
+        assert!(hss_verify::<H>(&message, signature.as_ref(), verifying_key.as_slice()).is_ok());
+
+        let lmots_parameter =
+            *HssParameter::<H>::construct_default_parameters().get_lmots_parameter();
+        let target = lmots_parameter.get_max_hash_iterations() / 2 - 10;
+        let mut message = [42u8; 64];
+        message[64 - MAX_HASH_SIZE..].fill(0);
+        let signature = sign_mut(
+            &mut message,
+            &FastVerifyOptions::default()
+                .with_max_hash_optimizations(1_000_000)
+                .with_threads(2)
+                .with_target_verify_chain_steps(target),
+        );
+        assert!(signature.verify_chain_steps.unwrap() <= target as u32);
+        assert!(hss_verify::<H>(&message, signature.as_ref(), verifying_key.as_slice()).is_ok());
+
+        // Zero threads are rejected
+        let signing_key_const = signing_key.duplicate();
+        assert!(hss_sign_mut::<H>(
+            &mut [42u8; 64],
+            signing_key_const.expose_secret(),
+            &mut |_: &[u8]| Ok(()),
+            None,
+            &FastVerifyOptions::default().with_threads(0),
+        )
+        .is_err());
+    }
+
+    #[cfg(feature = "fast_verify_core")]
+    #[test]
+    fn fast_verify_with_caller_rng() {
+        use rand::{rngs::StdRng, SeedableRng};
+
+        type H = Sha256_256;
+        let seed = gen_random_seed::<H>();
+
+        let (signing_key, verifying_key) =
+            hss_keygen::<H>(&[HssParameter::construct_default_parameters()], &seed, None)
+                .expect("Should generate HSS keys");
+
+        let fast_verify_options = FastVerifyOptions::default().with_max_hash_optimizations(100);
+        #[cfg(feature = "fast_verify")]
+        let fast_verify_options = fast_verify_options.with_threads(1);
+
+        let mut messages = [[42u8; 64]; 2];
+        for message in messages.iter_mut() {
+            message[64 - MAX_HASH_SIZE..].fill(0);
+
+            let mut signing_key = signing_key.duplicate();
+            let signing_key_const = signing_key.duplicate();
+            let signature = hss_sign_mut_with_rng::<H>(
+                message,
+                signing_key_const.expose_secret(),
+                &mut |new_key: &[u8]| {
+                    signing_key.expose_secret_mut().copy_from_slice(new_key);
+                    Ok(())
+                },
+                None,
+                &fast_verify_options,
+                &mut StdRng::seed_from_u64(42),
+            )
+            .expect("Signing should complete without error.");
+
+            assert!(hss_verify::<H>(message, signature.as_ref(), verifying_key.as_slice()).is_ok());
+        }
+
+        // The randomizers tried only depend on the RNG of the caller
+        assert_ne!(messages[0][64 - MAX_HASH_SIZE..], [0u8; MAX_HASH_SIZE]);
+        assert_eq!(messages[0], messages[1]);
+    }
 }
@@ -1,6 +1,7 @@
 use crate::{
-    hasher::HashChain, lm_ots::parameters::LmotsParameter, lms::parameters::LmsParameter,
-    LmotsAlgorithm, LmsAlgorithm,
+    constants::SIGNED_PUBLIC_KEY_HASH_OPTIMIZATIONS, hasher::HashChain,
+    lm_ots::parameters::LmotsParameter, lms::parameters::LmsParameter, LmotsAlgorithm,
+    LmsAlgorithm,
 };
 
 /**
@@ -11,6 +12,8 @@ use crate::{
 pub struct HssParameter<H: HashChain> {
     lmots_parameter: LmotsParameter<H>,
     lms_parameter: LmsParameter<H>,
+    signed_public_key_trials: Option<usize>,
+    is_cached_in_aux_data: bool,
 }
 
 impl<H: HashChain> Copy for HssParameter<H> {}
@@ -26,9 +29,58 @@ impl<H: HashChain> HssParameter<H> {
         HssParameter {
             lmots_parameter,
             lms_parameter,
+            signed_public_key_trials: None,
+            is_cached_in_aux_data: false,
         }
     }
 
+    /**
+     * Lets the parent level sign the public key of this level with a fast verifiable signature,
+     * which speeds up the verification of every signature of this tree. Not applicable to the top
+     * level.
+     *
+     * The randomizer is searched deterministically on every expansion of the private key, which
+     * adds a few thousand hash compressions to each signing. The choice and the count of
+     * randomizers tried are stored in the private key, which is then no longer compatible with
+     * the reference implementation.
+     */
+    pub fn with_fast_verify_signed_public_key(self) -> Self {
+        self.with_signed_public_key_trials(SIGNED_PUBLIC_KEY_HASH_OPTIMIZATIONS)
+    }
+
+    pub(crate) fn with_signed_public_key_trials(mut self, trials: usize) -> Self {
+        self.signed_public_key_trials = Some(trials);
+        self
+    }
+
+    pub fn is_fast_verify_signed_public_key(&self) -> bool {
+        self.signed_public_key_trials.is_some()
+    }
+
+    /// Count of randomizers tried for the signed public key of this level, if it is fast verifiable
+    pub fn get_signed_public_key_trials(&self) -> Option<usize> {
+        self.signed_public_key_trials
+    }
+
+    /**
+     * Lets [`crate::keygen`] cache the active tree of this level in the bytes of the aux data left
+     * by the levels above, which then have to be cached as well. Not applicable to the top level,
+     * whose tree is cached whenever there is aux data.
+     *
+     * The cache is appended to the aux data of the top tree and rewritten while signing, whenever
+     * the tree of this level is replaced. Older versions of this crate reject such aux data and
+     * sign without it. The choice is kept in the aux data, not in the private key, hence
+     * [`crate::SigningKey::regenerate_aux`] only caches the top tree.
+     */
+    pub fn with_aux_data_cache(mut self) -> Self {
+        self.is_cached_in_aux_data = true;
+        self
+    }
+
+    pub fn is_cached_in_aux_data(&self) -> bool {
+        self.is_cached_in_aux_data
+    }
+
     pub fn get_lmots_parameter(&self) -> &LmotsParameter<H> {
         &self.lmots_parameter
     }
@@ -1,16 +1,18 @@
 use crate::{
     constants::{
-        LmsTreeIdentifier, D_TOPSEED, ILEN, LMS_LEAF_IDENTIFIERS_SIZE, MAX_ALLOWED_HSS_LEVELS,
-        MAX_HASH_SIZE, MAX_SEED_LEN, REF_IMPL_MAX_PRIVATE_KEY_SIZE, SEED_CHILD_SEED,
-        SEED_SIGNATURE_RANDOMIZER_SEED, TOPSEED_D, TOPSEED_LEN, TOPSEED_SEED, TOPSEED_WHICH,
+        LmsTreeIdentifier, D_TOPSEED, EXTENDED_PARAMETER_SET_SIZE, EXTENDED_PRIVATE_KEY_SIZE, ILEN,
+        LMS_LEAF_IDENTIFIERS_SIZE, MAX_ALLOWED_HSS_LEVELS, MAX_HASH_SIZE, MAX_PRIVATE_KEY_SIZE,
+        MAX_SEED_LEN, PRNG_MAX_LEN, REF_IMPL_MAX_ALLOWED_HSS_LEVELS, REF_IMPL_MAX_PRIVATE_KEY_SIZE,
+        SEED_CHILD_SEED, SEED_SIGNATURE_RANDOMIZER_SEED, TOPSEED_D, TOPSEED_LEN, TOPSEED_SEED,
+        TOPSEED_WHICH,
     },
-    hasher::HashChain,
+    hasher::{HashChain, HashOperations},
     hss::{definitions::HssPrivateKey, seed_derive::SeedDerive},
     util::{helper::read_and_advance, ArrayVecZeroize},
     HssParameter, LmotsAlgorithm, LmsAlgorithm,
 };
 
-use core::{convert::TryFrom, convert::TryInto, marker::PhantomData};
+use core::{convert::TryFrom, convert::TryInto, marker::PhantomData, ops::Range};
 use tinyvec::ArrayVec;
 use zeroize::{Zeroize, ZeroizeOnDrop};
 
@@ -39,7 +41,7 @@ impl<H: HashChain> From<[u8; MAX_SEED_LEN]> for Seed<H> {
     fn from(data: [u8; MAX_SEED_LEN]) -> Self {
         Seed {
             data: ArrayVecZeroize(ArrayVec::from_array_len(data, MAX_SEED_LEN)),
-            phantom: PhantomData::default(),
+            phantom: PhantomData,
         }
     }
 }
@@ -51,7 +53,7 @@ impl<H: HashChain> TryFrom<ArrayVec<[u8; MAX_SEED_LEN]>> for Seed<H> {
         if value.len() == H::OUTPUT_SIZE as usize {
             Ok(Seed {
                 data: ArrayVecZeroize(value),
-                phantom: PhantomData::default(),
+                phantom: PhantomData,
             })
         } else {
             Err("Can only construct seed from data of the HashChain output length")
@@ -82,11 +84,25 @@ impl<H: HashChain> SeedAndLmsTreeIdentifier<H> {
     }
 }
 
+/**
+The signature indices `start..end` a shard of a private key is restricted to.
+
+Shards are stored with the extended encoding of the parameter set and the range following the
+used leafs counter. Hence, neither the reference implementation nor a version of this crate
+without sharding can load a shard and sign beyond its range.
+ */
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Zeroize)]
+pub struct ShardRange {
+    pub start: u64,
+    pub end: u64,
+}
+
 #[derive(Clone, Default, PartialEq, Eq, Zeroize, ZeroizeOnDrop)]
 pub struct ReferenceImplPrivateKey<H: HashChain> {
     pub compressed_used_leafs_indexes: CompressedUsedLeafsIndexes,
     pub compressed_parameter: CompressedParameterSet,
     pub seed: Seed<H>,
+    pub shard_range: Option<ShardRange>,
 }
 
 impl<H: HashChain> ReferenceImplPrivateKey<H> {
@@ -94,6 +110,10 @@ impl<H: HashChain> ReferenceImplPrivateKey<H> {
         self.seed = Seed::default();
         self.compressed_parameter = CompressedParameterSet::default();
         self.compressed_used_leafs_indexes = CompressedUsedLeafsIndexes::new(0);
+        // An empty range keeps the length of the encoding of shards
+        if let Some(shard_range) = self.shard_range.as_mut() {
+            *shard_range = ShardRange::default();
+        }
     }
 
     pub fn generate(parameters: &[HssParameter<H>], seed: &Seed<H>) -> Result<Self, ()> {
@@ -101,25 +121,50 @@ impl<H: HashChain> ReferenceImplPrivateKey<H> {
             compressed_used_leafs_indexes: CompressedUsedLeafsIndexes::new(0),
             compressed_parameter: CompressedParameterSet::from(parameters)?,
             seed: seed.clone(),
+            shard_range: None,
         };
 
         Ok(private_key)
     }
 
-    pub fn to_binary_representation(&self) -> ArrayVec<[u8; REF_IMPL_MAX_PRIVATE_KEY_SIZE]> {
+    pub fn to_binary_representation(&self) -> ArrayVec<[u8; MAX_PRIVATE_KEY_SIZE]> {
         let mut result = ArrayVec::new();
 
         result.extend_from_slice(&self.compressed_used_leafs_indexes.count.to_be_bytes());
-        result.extend_from_slice(&self.compressed_parameter.0);
+        if let Some(shard_range) = self.shard_range {
+            result.extend_from_slice(&shard_range.start.to_be_bytes());
+            result.extend_from_slice(&shard_range.end.to_be_bytes());
+            result.extend_from_slice(
+                self.compressed_parameter
+                    .to_extended_binary_representation()
+                    .as_slice(),
+            );
+        } else {
+            result.extend_from_slice(
+                self.compressed_parameter
+                    .to_binary_representation()
+                    .as_slice(),
+            );
+        }
         result.extend_from_slice(self.seed.as_slice());
 
         result
     }
 
     pub fn from_binary_representation(data: &[u8]) -> Result<Self, ()> {
-        if data.len() != REF_IMPL_MAX_PRIVATE_KEY_SIZE - MAX_SEED_LEN + H::OUTPUT_SIZE as usize {
-            return Err(());
-        }
+        // The key length tells apart the reference implementation, the extended encoding
+        // of the parameter set and shards, which always use the extended encoding.
+        let seed_len = H::OUTPUT_SIZE as usize;
+        let (compressed_parameter_len, is_shard) =
+            if data.len() == REF_IMPL_MAX_PRIVATE_KEY_SIZE - MAX_SEED_LEN + seed_len {
+                (REF_IMPL_MAX_ALLOWED_HSS_LEVELS, false)
+            } else if data.len() == EXTENDED_PRIVATE_KEY_SIZE - MAX_SEED_LEN + seed_len {
+                (EXTENDED_PARAMETER_SET_SIZE, false)
+            } else if data.len() == MAX_PRIVATE_KEY_SIZE - MAX_SEED_LEN + seed_len {
+                (EXTENDED_PARAMETER_SET_SIZE, true)
+            } else {
+                return Err(());
+            };
 
         let mut result = Self::default();
         let mut index = 0;
@@ -129,19 +174,134 @@ impl<H: HashChain> ReferenceImplPrivateKey<H> {
         result.compressed_used_leafs_indexes =
             CompressedUsedLeafsIndexes::from_slice(compressed_used_leafs_indexes);
 
-        let compressed_parameter = read_and_advance(data, MAX_ALLOWED_HSS_LEVELS, &mut index);
+        if is_shard {
+            let mut read_index = || {
+                u64::from_be_bytes(
+                    read_and_advance(data, LMS_LEAF_IDENTIFIERS_SIZE, &mut index)
+                        .try_into()
+                        .unwrap(),
+                )
+            };
+            result.shard_range = Some(ShardRange {
+                start: read_index(),
+                end: read_index(),
+            });
+        }
+
+        let compressed_parameter = read_and_advance(data, compressed_parameter_len, &mut index);
         result.compressed_parameter = CompressedParameterSet::from_slice(compressed_parameter)?;
 
-        let seed_len = result.seed.len();
         result
             .seed
             .as_mut_slice()
             .copy_from_slice(read_and_advance(data, seed_len, &mut index));
 
+        if let Some(shard_range) = result.shard_range {
+            let count = result.compressed_used_leafs_indexes.count;
+            let end = result.total_signatures()?;
+
+            if count < shard_range.start || count >= shard_range.end || shard_range.end > end {
+                return Err(());
+            }
+        }
+
         Ok(result)
     }
 
-    pub fn generate_root_seed_and_lms_tree_identifier(&self) -> SeedAndLmsTreeIdentifier<H> {
+    /// Signatures of the whole key, saturated at `u64::MAX`
+    fn total_signatures(&self) -> Result<u64, ()> {
+        let total_tree_height: u32 = self
+            .compressed_parameter
+            .to::<H>()?
+            .iter()
+            .map(|parameter| u32::from(parameter.get_lms_parameter().get_tree_height()))
+            .sum();
+
+        Ok(1u64.checked_shl(total_tree_height).unwrap_or(u64::MAX))
+    }
+
+    /// Signature indices this key may still use
+    pub fn remaining_signatures(&self) -> Result<Range<u64>, ()> {
+        let end = match self.shard_range {
+            Some(shard_range) => shard_range.end,
+            None => self.total_signatures()?,
+        };
+
+        Ok(self.compressed_used_leafs_indexes.count..end)
+    }
+
+    /// Skip all signature indices below `count`, which wipes the key if none are left
+    pub fn skip_to(&mut self, count: u64) -> Result<(), ()> {
+        let remaining = self.remaining_signatures()?;
+
+        if count < remaining.start {
+            return Err(());
+        }
+        if count >= remaining.end {
+            self.wipe();
+        } else {
+            self.compressed_used_leafs_indexes = CompressedUsedLeafsIndexes::new(count);
+        }
+        Ok(())
+    }
+
+    /**
+    Split off the signature indices from `index` on into a shard, which restricts this key to the
+    indices below `index`.
+     */
+    pub fn split_off(&mut self, index: u64) -> Result<Self, ()> {
+        let remaining = self.remaining_signatures()?;
+
+        if index <= remaining.start || index >= remaining.end {
+            return Err(());
+        }
+
+        let mut split = self.clone();
+        split.compressed_used_leafs_indexes = CompressedUsedLeafsIndexes::new(index);
+        split.shard_range = Some(ShardRange {
+            start: index,
+            end: remaining.end,
+        });
+
+        self.shard_range = Some(ShardRange {
+            start: self.shard_range.map_or(0, |shard_range| shard_range.start),
+            end: index,
+        });
+
+        Ok(split)
+    }
+
+    /**
+    Restrict a copy of this key to the shard `shard_index` of `shard_count` equally sized shards
+    of the remaining signature indices.
+     */
+    pub fn shard(&self, shard_index: u64, shard_count: u64) -> Result<Self, ()> {
+        let remaining = self.remaining_signatures()?;
+        let len = remaining.end - remaining.start;
+
+        if shard_count == 0 || shard_count > len || shard_index >= shard_count {
+            return Err(());
+        }
+
+        let bound = |index: u64| {
+            remaining.start + (u128::from(len) * u128::from(index) / u128::from(shard_count)) as u64
+        };
+        let shard_range = ShardRange {
+            start: bound(shard_index),
+            end: bound(shard_index + 1),
+        };
+
+        let mut shard = self.clone();
+        shard.compressed_used_leafs_indexes = CompressedUsedLeafsIndexes::new(shard_range.start);
+        shard.shard_range = Some(shard_range);
+
+        Ok(shard)
+    }
+
+    pub fn generate_root_seed_and_lms_tree_identifier(
+        &self,
+        hash_operations: &mut HashOperations,
+    ) -> SeedAndLmsTreeIdentifier<H> {
         let mut hash_preimage = [0u8; TOPSEED_LEN];
         let mut hash_postimage =
             ArrayVec::from_array_len([0u8; MAX_HASH_SIZE], H::OUTPUT_SIZE as usize);
@@ -170,6 +330,7 @@ impl<H: HashChain> ReferenceImplPrivateKey<H> {
 
         let mut lms_tree_identifier = LmsTreeIdentifier::default();
         lms_tree_identifier.copy_from_slice(&hasher.finalize_reset()[..ILEN]);
+        hash_operations.add_seed_derive(3 * HashOperations::compressions::<H>(TOPSEED_LEN));
 
         SeedAndLmsTreeIdentifier::new(&seed, &lms_tree_identifier)
     }
@@ -180,15 +341,23 @@ impl<H: HashChain> ReferenceImplPrivateKey<H> {
             .iter()
             .map(|pk| pk.lms_parameter.get_tree_height())
             .collect();
-        self.compressed_used_leafs_indexes
+        let is_exhausted = self
+            .compressed_used_leafs_indexes
             .increment(&tree_heights)
-            .unwrap_or_else(|_| self.wipe());
+            .is_err()
+            || matches!(self.shard_range, Some(shard_range)
+                if self.compressed_used_leafs_indexes.count >= shard_range.end);
+
+        if is_exhausted {
+            self.wipe();
+        }
     }
 }
 
 pub fn generate_child_seed_and_lms_tree_identifier<H: HashChain>(
     parent_seed: &SeedAndLmsTreeIdentifier<H>,
     parent_lms_leaf_identifier: &u32,
+    hash_operations: &mut HashOperations,
 ) -> SeedAndLmsTreeIdentifier<H> {
     let mut derive = SeedDerive::new(&parent_seed.seed, &parent_seed.lms_tree_identifier);
 
@@ -198,6 +367,7 @@ pub fn generate_child_seed_and_lms_tree_identifier<H: HashChain>(
     let seed = Seed::try_from(derive.seed_derive(true)).unwrap();
     let mut lms_tree_identifier = LmsTreeIdentifier::default();
     lms_tree_identifier.copy_from_slice(&derive.seed_derive(false)[..ILEN]);
+    hash_operations.add_seed_derive(2 * HashOperations::compressions::<H>(PRNG_MAX_LEN));
 
     SeedAndLmsTreeIdentifier::new(&seed, &lms_tree_identifier)
 }
@@ -205,79 +375,224 @@ pub fn generate_child_seed_and_lms_tree_identifier<H: HashChain>(
 pub fn generate_signature_randomizer<H: HashChain>(
     child_seed: &SeedAndLmsTreeIdentifier<H>,
     parent_lms_leaf_identifier: &u32,
+    hash_operations: &mut HashOperations,
 ) -> ArrayVec<[u8; MAX_HASH_SIZE]> {
     let mut derive = SeedDerive::new(&child_seed.seed, &child_seed.lms_tree_identifier);
 
     derive.set_lms_leaf_identifier(*parent_lms_leaf_identifier);
     derive.set_child_seed(SEED_SIGNATURE_RANDOMIZER_SEED);
+    hash_operations.add_seed_derive(HashOperations::compressions::<H>(PRNG_MAX_LEN));
 
     derive.seed_derive(false)
 }
 
 const PARAM_SET_END: u8 = 0xff; // Marker for end of parameter set
+const PARAM_SET_NIBBLE_MAX: u8 = 0x0f; // Largest type code fitting the reference encoding
+const PARAM_SET_FAST_VERIFY: u8 = 0x80; // Flag in the LM-OTS type for fast verifiable signed keys
+const PARAM_SET_TRIALS_MASK: u8 = 0x60; // Randomizers tried for fast verifiable signed keys
+const PARAM_SET_TRIALS_SHIFT: u8 = 5;
+const PARAM_SET_TYPE_MASK: u8 = 0x1f; // LM-OTS type next to the flag and the trials
+const PARAM_SET_TRIALS_MIN_LOG2: u32 = 10;
+
+/// Randomizers tried for the trials code `code`, which is stored in the private key
+fn signed_public_key_trials(code: u8) -> usize {
+    1 << (PARAM_SET_TRIALS_MIN_LOG2 + 2 * u32::from(code))
+}
 
+/**
+The type codes of all HSS levels as stored in the private key.
+
+The reference implementation packs both type codes of a level into a single byte
+(`(lms_type << 4) + lmots_type`), which can only hold codes up to 15. Parameter sets with larger
+codes (e.g. the SHA-256/192 and SHAKE families of SP 800-208) are stored in the extended encoding,
+which uses one byte for each type code instead. All other parameter sets keep the reference
+implementation encoding, so that keys stay exchangeable with it. The type codes generated by this
+crate all fit the reference implementation encoding, so larger codes only come from keys of other
+implementations, while keys generated by this crate need the extended encoding only for the flag
+below.
+
+Levels with a fast verifiable signed public key (see
+[`HssParameter::with_fast_verify_signed_public_key`]) set a flag in the LM-OTS type code, which
+forces the extended encoding. The reference implementation would sign these public keys with a
+different randomizer and must not be able to load such keys. The two bits below the flag store the
+count of randomizers tried (`code`, for `2^(10 + 2 * code)` randomizers), since the signatures are
+recomputed with it on every expansion of the key. The remaining five bits hold the type code.
+ */
 #[derive(Clone, PartialEq, Eq, Zeroize, ZeroizeOnDrop)]
-pub struct CompressedParameterSet([u8; MAX_ALLOWED_HSS_LEVELS]);
+pub struct CompressedParameterSet {
+    lms_types: [u8; REF_IMPL_MAX_ALLOWED_HSS_LEVELS],
+    lmots_types: [u8; REF_IMPL_MAX_ALLOWED_HSS_LEVELS],
+}
 
 impl Default for CompressedParameterSet {
     fn default() -> Self {
-        Self([PARAM_SET_END; MAX_ALLOWED_HSS_LEVELS])
+        Self {
+            lms_types: [PARAM_SET_END; REF_IMPL_MAX_ALLOWED_HSS_LEVELS],
+            lmots_types: [PARAM_SET_END; REF_IMPL_MAX_ALLOWED_HSS_LEVELS],
+        }
     }
 }
 
 impl CompressedParameterSet {
     pub fn from_slice(data: &[u8]) -> Result<Self, ()> {
-        if data.len() != MAX_ALLOWED_HSS_LEVELS {
-            return Err(());
-        }
-
         let mut result = CompressedParameterSet::default();
-        result.0.copy_from_slice(data);
+
+        match data.len() {
+            REF_IMPL_MAX_ALLOWED_HSS_LEVELS => {
+                for (level, &parameter) in data.iter().enumerate() {
+                    if parameter == PARAM_SET_END {
+                        break;
+                    }
+                    result.lms_types[level] = parameter >> 4;
+                    result.lmots_types[level] = parameter & 0x0f;
+                }
+            }
+            EXTENDED_PARAMETER_SET_SIZE => {
+                for (level, parameter) in data.chunks_exact(2).enumerate() {
+                    let (lms_type, lmots_type) = (parameter[0], parameter[1]);
+
+                    if lms_type == PARAM_SET_END && lmots_type == PARAM_SET_END {
+                        break;
+                    }
+                    if lms_type == PARAM_SET_END || lmots_type == PARAM_SET_END {
+                        return Err(());
+                    }
+                    result.lms_types[level] = lms_type;
+                    result.lmots_types[level] = lmots_type;
+                }
+            }
+            _ => return Err(()),
+        }
 
         Ok(result)
     }
 
     pub fn from<H: HashChain>(parameters: &[HssParameter<H>]) -> Result<Self, ()> {
+        if parameters.len() > REF_IMPL_MAX_ALLOWED_HSS_LEVELS {
+            return Err(());
+        }
+
         let mut result = CompressedParameterSet::default();
 
         for (i, parameter) in parameters.iter().enumerate() {
-            let lmots = parameter.get_lmots_parameter();
-            let lms = parameter.get_lms_parameter();
+            let lmots_type = parameter.get_lmots_parameter().get_type_id();
+            let lms_type = parameter.get_lms_parameter().get_type_id();
 
-            let lmots_type = lmots.get_type_id() as u8;
-            let lms_type = lms.get_type_id() as u8;
+            if lms_type >= PARAM_SET_END as u32 || lmots_type >= PARAM_SET_FAST_VERIFY as u32 {
+                return Err(());
+            }
 
-            result.0[i] = (lms_type << 4) + lmots_type;
+            result.lms_types[i] = lms_type as u8;
+            result.lmots_types[i] = lmots_type as u8;
+
+            if let Some(trials) = parameter.get_signed_public_key_trials() {
+                let code = (0..=PARAM_SET_TRIALS_MASK >> PARAM_SET_TRIALS_SHIFT)
+                    .find(|&code| signed_public_key_trials(code) == trials);
+                let code = match code {
+                    Some(code) if i != 0 && lmots_type <= PARAM_SET_TYPE_MASK as u32 => code,
+                    _ => return Err(()),
+                };
+                result.lmots_types[i] |= PARAM_SET_FAST_VERIFY | code << PARAM_SET_TRIALS_SHIFT;
+            }
         }
 
         Ok(result)
     }
 
-    pub fn to<H: HashChain>(
-        &self,
-    ) -> Result<ArrayVec<[HssParameter<H>; MAX_ALLOWED_HSS_LEVELS]>, ()> {
-        let mut result = ArrayVec::new();
+    fn levels(&self) -> usize {
+        self.lms_types
+            .iter()
+            .position(|&lms_type| lms_type == PARAM_SET_END)
+            .unwrap_or(REF_IMPL_MAX_ALLOWED_HSS_LEVELS)
+    }
 
-        for level in 0..MAX_ALLOWED_HSS_LEVELS {
-            let parameter = self.0[level];
+    /// Whether the parameter set can be stored in the encoding of the reference implementation.
+    pub fn is_reference_compatible(&self) -> bool {
+        (0..self.levels()).all(|level| {
+            let (lms_type, lmots_type) = (self.lms_types[level], self.lmots_types[level]);
 
-            if parameter == PARAM_SET_END {
-                break;
-            }
+            lms_type <= PARAM_SET_NIBBLE_MAX
+                && lmots_type <= PARAM_SET_NIBBLE_MAX
+                && (lms_type << 4) + lmots_type != PARAM_SET_END
+        })
+    }
 
-            let lms_type = parameter >> 4;
-            let lmots_type = parameter & 0x0f;
+    pub fn to_binary_representation(&self) -> ArrayVec<[u8; EXTENDED_PARAMETER_SET_SIZE]> {
+        self.encode(!self.is_reference_compatible())
+    }
 
-            let lms = LmsAlgorithm::from(lms_type as u32);
-            let lmots = LmotsAlgorithm::from(lmots_type as u32);
+    pub fn to_extended_binary_representation(&self) -> ArrayVec<[u8; EXTENDED_PARAMETER_SET_SIZE]> {
+        self.encode(true)
+    }
 
-            result.extend_from_slice(&[HssParameter::new(lmots, lms)]);
+    fn encode(&self, is_extended: bool) -> ArrayVec<[u8; EXTENDED_PARAMETER_SET_SIZE]> {
+        let mut result = ArrayVec::new();
+        let levels = self.levels();
+
+        if !is_extended {
+            for level in 0..REF_IMPL_MAX_ALLOWED_HSS_LEVELS {
+                if level < levels {
+                    result.push((self.lms_types[level] << 4) + self.lmots_types[level]);
+                } else {
+                    result.push(PARAM_SET_END);
+                }
+            }
+        } else {
+            for level in 0..REF_IMPL_MAX_ALLOWED_HSS_LEVELS {
+                if level < levels {
+                    result.push(self.lms_types[level]);
+                    result.push(self.lmots_types[level]);
+                } else {
+                    result.push(PARAM_SET_END);
+                    result.push(PARAM_SET_END);
+                }
+            }
         }
 
-        if result.is_empty() {
+        result
+    }
+
+    pub fn to<H: HashChain>(
+        &self,
+    ) -> Result<ArrayVec<[HssParameter<H>; MAX_ALLOWED_HSS_LEVELS]>, ()> {
+        let levels = self.levels();
+
+        if levels == 0 || levels > MAX_ALLOWED_HSS_LEVELS {
             return Err(());
         }
 
+        let mut result = ArrayVec::new();
+
+        for level in 0..levels {
+            let fast_verify = self.lmots_types[level] & PARAM_SET_FAST_VERIFY != 0;
+            let lms = LmsAlgorithm::from(self.lms_types[level] as u32);
+            let lmots = LmotsAlgorithm::from(if fast_verify {
+                (self.lmots_types[level] & PARAM_SET_TYPE_MASK) as u32
+            } else {
+                self.lmots_types[level] as u32
+            });
+
+            if fast_verify && level == 0 {
+                return Err(());
+            }
+
+            // Unknown type codes would otherwise be turned into the reserved algorithms.
+            if lms.construct_parameter::<H>().is_none()
+                || lmots.construct_parameter::<H>().is_none()
+            {
+                return Err(());
+            }
+
+            let parameter = HssParameter::new(lmots, lms);
+            result.push(if fast_verify {
+                parameter.with_signed_public_key_trials(signed_public_key_trials(
+                    (self.lmots_types[level] & PARAM_SET_TRIALS_MASK) >> PARAM_SET_TRIALS_SHIFT,
+                ))
+            } else {
+                parameter
+            });
+        }
+
         Ok(result)
     }
 }
@@ -292,6 +607,10 @@ impl CompressedUsedLeafsIndexes {
         CompressedUsedLeafsIndexes { count }
     }
 
+    pub fn count(&self) -> u64 {
+        self.count
+    }
+
     pub fn from_slice(data: &[u8]) -> Self {
         CompressedUsedLeafsIndexes {
             count: u64::from_be_bytes(data.try_into().unwrap()),
@@ -300,7 +619,7 @@ impl CompressedUsedLeafsIndexes {
 
     pub fn to<H: HashChain>(
         &self,
-        parameters: &ArrayVec<[HssParameter<H>; MAX_ALLOWED_HSS_LEVELS]>,
+        parameters: &[HssParameter<H>],
     ) -> [u32; MAX_ALLOWED_HSS_LEVELS] {
         let mut lms_leaf_identifier_set = [0u32; MAX_ALLOWED_HSS_LEVELS];
         let mut compressed_used_leafs_indexes = self.count;
@@ -331,10 +650,14 @@ impl CompressedUsedLeafsIndexes {
 
 #[cfg(test)]
 mod tests {
-    use super::{CompressedParameterSet, ReferenceImplPrivateKey};
+    use super::{CompressedParameterSet, ReferenceImplPrivateKey, ShardRange, PARAM_SET_END};
     use crate::{
-        constants::MAX_ALLOWED_HSS_LEVELS, hss::definitions::HssPrivateKey, HssParameter,
-        LmotsAlgorithm, LmsAlgorithm, Sha256_256,
+        constants::{
+            EXTENDED_PARAMETER_SET_SIZE, EXTENDED_PRIVATE_KEY_SIZE, MAX_ALLOWED_HSS_LEVELS,
+            MAX_PRIVATE_KEY_SIZE, REF_IMPL_MAX_ALLOWED_HSS_LEVELS, REF_IMPL_MAX_PRIVATE_KEY_SIZE,
+        },
+        hss::{definitions::HssPrivateKey, hss_keygen, hss_sign, hss_verify},
+        HssParameter, LmotsAlgorithm, LmsAlgorithm, Sha256_256,
     };
 
     use crate::util::helper::test_helper::gen_random_seed;
@@ -351,7 +674,8 @@ mod tests {
         let seed = gen_random_seed::<Hasher>();
         let mut rfc_private_key = ReferenceImplPrivateKey::generate(&parameters, &seed).unwrap();
 
-        let hss_private_key = HssPrivateKey::from(&rfc_private_key, &mut None).unwrap();
+        let hss_private_key =
+            HssPrivateKey::from(&rfc_private_key, &mut None, &mut Default::default()).unwrap();
 
         let seed = rfc_private_key.seed.clone();
 
@@ -378,7 +702,8 @@ mod tests {
         let seed = gen_random_seed::<Hasher>();
         let mut rfc_private_key = ReferenceImplPrivateKey::generate(&parameters, &seed).unwrap();
 
-        let hss_private_key = HssPrivateKey::from(&rfc_private_key, &mut None).unwrap();
+        let hss_private_key =
+            HssPrivateKey::from(&rfc_private_key, &mut None, &mut Default::default()).unwrap();
         let keypair_lifetime = hss_private_key.get_lifetime();
 
         for _ in 0..keypair_lifetime {
@@ -414,7 +739,136 @@ mod tests {
             assert!(p == &parameter[i])
         }
 
-        assert!(compressed == CompressedParameterSet::from_slice(&compressed.0).unwrap());
+        let binary_representation = compressed.to_binary_representation();
+        assert_eq!(binary_representation.len(), REF_IMPL_MAX_ALLOWED_HSS_LEVELS);
+        assert_eq!(binary_representation[..2], [0x53, 0x64]);
+        assert!(compressed == CompressedParameterSet::from_slice(&binary_representation).unwrap());
+    }
+
+    // LMS and LM-OTS type codes of RFC 8554 and NIST SP 800-208
+    const LMS_TYPE_CODES: core::ops::RangeInclusive<u8> = 0x05..=0x18;
+    const LMOTS_TYPE_CODES: core::ops::RangeInclusive<u8> = 0x01..=0x10;
+
+    #[test]
+    fn parameter_set_round_trips_all_type_codes() {
+        for lms_type in LMS_TYPE_CODES {
+            for lmots_type in LMOTS_TYPE_CODES {
+                let mut compressed = CompressedParameterSet::default();
+                compressed.lms_types[..2].copy_from_slice(&[0x05, lms_type]);
+                compressed.lmots_types[..2].copy_from_slice(&[lmots_type, 0x01]);
+
+                let binary_representation = compressed.to_binary_representation();
+
+                let fits_reference_encoding =
+                    lms_type <= 0x0f && lmots_type <= 0x0f && (lms_type << 4) + 1 != PARAM_SET_END;
+                if fits_reference_encoding {
+                    assert_eq!(binary_representation.len(), REF_IMPL_MAX_ALLOWED_HSS_LEVELS);
+                } else {
+                    assert_eq!(binary_representation.len(), EXTENDED_PARAMETER_SET_SIZE);
+                }
+
+                let deserialized =
+                    CompressedParameterSet::from_slice(&binary_representation).unwrap();
+                assert!(deserialized == compressed);
+                assert_eq!(deserialized.lms_types[1], lms_type);
+                assert_eq!(deserialized.lmots_types[0], lmots_type);
+            }
+        }
+    }
+
+    #[test]
+    fn parse_reference_implementation_private_key() {
+        // Private key as written by the reference implementation for the parameters `5/1,5/1`
+        let mut reference_key = [0u8; REF_IMPL_MAX_PRIVATE_KEY_SIZE];
+        reference_key[..8].copy_from_slice(&3u64.to_be_bytes());
+        reference_key[8..16].copy_from_slice(&[0x51, 0x51, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff]);
+        reference_key[16..].copy_from_slice(&[0x42; 32]);
+
+        let key =
+            ReferenceImplPrivateKey::<Hasher>::from_binary_representation(&reference_key).unwrap();
+
+        let parameters = key.compressed_parameter.to::<Hasher>().unwrap();
+        let expected = HssParameter::new(LmotsAlgorithm::LmotsW1, LmsAlgorithm::LmsH5);
+        assert_eq!(parameters.len(), 2);
+        assert!(parameters.iter().all(|parameter| parameter == &expected));
+
+        assert_eq!(key.to_binary_representation().as_slice(), reference_key);
+    }
+
+    #[test]
+    fn private_key_with_extended_parameter_set() {
+        let seed = gen_random_seed::<Hasher>();
+        let mut key = ReferenceImplPrivateKey::generate(
+            &[HssParameter::construct_default_parameters()],
+            &seed,
+        )
+        .unwrap();
+        key.compressed_parameter.lms_types[1] = 0x14;
+        key.compressed_parameter.lmots_types[1] = 0x0d;
+
+        let binary_representation = key.to_binary_representation();
+        assert_eq!(binary_representation.len(), EXTENDED_PRIVATE_KEY_SIZE);
+
+        let deserialized = ReferenceImplPrivateKey::<Hasher>::from_binary_representation(
+            binary_representation.as_slice(),
+        )
+        .unwrap();
+        assert!(key == deserialized);
+
+        // Type codes unknown to this crate are rejected instead of being mapped to the reserved
+        // algorithms.
+        assert!(deserialized.compressed_parameter.to::<Hasher>().is_err());
+    }
+
+    #[test]
+    fn keygen_round_trip_with_extended_parameter_set() {
+        let seed = gen_random_seed::<Hasher>();
+        let parameters = [
+            HssParameter::new(LmotsAlgorithm::LmotsW2, LmsAlgorithm::LmsH2),
+            HssParameter::new(LmotsAlgorithm::LmotsW2, LmsAlgorithm::LmsH2)
+                .with_fast_verify_signed_public_key(),
+        ];
+        let (signing_key, verifying_key) = hss_keygen(&parameters, &seed, None).unwrap();
+        let private_key = signing_key.expose_secret();
+        assert_eq!(private_key.len(), EXTENDED_PRIVATE_KEY_SIZE);
+
+        let key =
+            ReferenceImplPrivateKey::<Hasher>::from_binary_representation(private_key).unwrap();
+        assert!(!key.compressed_parameter.is_reference_compatible());
+        assert!(key.compressed_parameter.to::<Hasher>().unwrap().as_slice() == parameters);
+        assert_eq!(key.to_binary_representation().as_slice(), private_key);
+
+        let message = b"extended parameter set";
+        let mut updated_key = [0u8; EXTENDED_PRIVATE_KEY_SIZE];
+        let signature = hss_sign::<Hasher>(
+            message,
+            private_key,
+            &mut |new_key| {
+                updated_key.copy_from_slice(new_key);
+                Ok(())
+            },
+            None,
+        )
+        .unwrap();
+        assert!(
+            hss_verify::<Hasher>(message, signature.as_ref(), verifying_key.as_slice()).is_ok()
+        );
+
+        let updated_key =
+            ReferenceImplPrivateKey::<Hasher>::from_binary_representation(&updated_key).unwrap();
+        assert!(updated_key.compressed_parameter == key.compressed_parameter);
+    }
+
+    #[test]
+    fn reject_malformed_parameter_set() {
+        let mut data = [PARAM_SET_END; EXTENDED_PARAMETER_SET_SIZE];
+        data[..2].copy_from_slice(&[0x05, PARAM_SET_END]);
+        assert!(CompressedParameterSet::from_slice(&data).is_err());
+
+        assert!(CompressedParameterSet::from_slice(&data[..3]).is_err());
+
+        let empty = CompressedParameterSet::from_slice(&[PARAM_SET_END; 8]).unwrap();
+        assert!(empty.to::<Hasher>().is_err());
     }
 
     #[test]
@@ -435,4 +889,99 @@ mod tests {
 
         assert!(key == deserialized);
     }
+
+    #[test]
+    fn shard_private_key() {
+        let parameters = [
+            HssParameter::<Hasher>::new(LmotsAlgorithm::LmotsW4, LmsAlgorithm::LmsH5),
+            HssParameter::new(LmotsAlgorithm::LmotsW4, LmsAlgorithm::LmsH5),
+        ];
+        let seed = gen_random_seed::<Hasher>();
+        let key = ReferenceImplPrivateKey::generate(&parameters, &seed).unwrap();
+        assert_eq!(key.remaining_signatures().unwrap(), 0..1024);
+
+        let shards = (0..3)
+            .map(|shard_index| key.shard(shard_index, 3).unwrap())
+            .collect::<ArrayVec<[ReferenceImplPrivateKey<Hasher>; 3]>>();
+        assert_eq!(shards[0].remaining_signatures().unwrap(), 0..341);
+        assert_eq!(shards[1].remaining_signatures().unwrap(), 341..682);
+        assert_eq!(shards[2].remaining_signatures().unwrap(), 682..1024);
+        assert!(key.shard(3, 3).is_err());
+        assert!(key.shard(0, 0).is_err());
+        assert!(key.shard(0, 1025).is_err());
+
+        // Shards always use the extended encoding and keep their range
+        let binary_representation = shards[1].to_binary_representation();
+        assert_eq!(binary_representation.len(), MAX_PRIVATE_KEY_SIZE);
+        let deserialized = ReferenceImplPrivateKey::<Hasher>::from_binary_representation(
+            binary_representation.as_slice(),
+        )
+        .unwrap();
+        assert!(deserialized == shards[1]);
+
+        // Counters outside of the range are rejected
+        for (count, shard_range) in [
+            (
+                340,
+                ShardRange {
+                    start: 341,
+                    end: 682,
+                },
+            ),
+            (
+                682,
+                ShardRange {
+                    start: 341,
+                    end: 682,
+                },
+            ),
+            (
+                1_000,
+                ShardRange {
+                    start: 1_000,
+                    end: 1_025,
+                },
+            ),
+        ] {
+            let mut forged = shards[1].clone();
+            forged.compressed_used_leafs_indexes.count = count;
+            forged.shard_range = Some(shard_range);
+            assert!(
+                ReferenceImplPrivateKey::<Hasher>::from_binary_representation(
+                    forged.to_binary_representation().as_slice()
+                )
+                .is_err()
+            );
+        }
+    }
+
+    #[test]
+    fn exhaust_shard() {
+        let parameters = [HssParameter::<Hasher>::new(
+            LmotsAlgorithm::LmotsW4,
+            LmsAlgorithm::LmsH5,
+        )];
+        let seed = gen_random_seed::<Hasher>();
+        let key = ReferenceImplPrivateKey::generate(&parameters, &seed).unwrap();
+        let mut shard = key.shard(1, 4).unwrap();
+
+        let hss_private_key =
+            HssPrivateKey::from(&shard, &mut None, &mut Default::default()).unwrap();
+
+        for _ in 0..7 {
+            assert_eq!(shard.seed, seed);
+            shard.increment(&hss_private_key);
+        }
+        assert_eq!(shard.remaining_signatures().unwrap(), 15..16);
+
+        shard.increment(&hss_private_key);
+        assert_ne!(shard.seed, seed);
+        assert_eq!(shard.shard_range, Some(ShardRange::default()));
+        assert!(
+            ReferenceImplPrivateKey::<Hasher>::from_binary_representation(
+                shard.to_binary_representation().as_slice()
+            )
+            .is_err()
+        );
+    }
 }
@@ -0,0 +1,452 @@
+use crate::{
+    constants::{D_STATE, MAX_HASH_SIZE, STATE_D, STATE_PREFIX_LEN},
+    hasher::HashChain,
+    hss::reference_impl_private_key::ReferenceImplPrivateKey,
+};
+
+use core::convert::TryInto;
+use signature::Error;
+use subtle::ConstantTimeEq;
+use tinyvec::ArrayVec;
+
+#[cfg(feature = "std")]
+use std::{
+    fs,
+    io::{self, Write},
+    marker::PhantomData,
+    path::{Path, PathBuf},
+};
+
+// Counter followed by the previous and the current fingerprint
+pub const MAX_COUNTER_STATE_SIZE: usize = 8 + 2 * MAX_HASH_SIZE;
+
+/**
+ * State of a signing key recorded by a [`MonotonicCounter`].
+ *
+ * `counter` is the count of signature indices the key has used. The fingerprint hashes the
+ * encoded private key together with the fingerprint of the previously recorded state, so it
+ * chains all recorded states of the key:
+ * `fingerprint = H(0^20 || D_STATE || previous_fingerprint || private_key)` with
+ * `D_STATE = 0xfbfb`, starting from an all-zero previous fingerprint.
+ */
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CounterState {
+    pub counter: u64,
+    pub previous_fingerprint: ArrayVec<[u8; MAX_HASH_SIZE]>,
+    pub fingerprint: ArrayVec<[u8; MAX_HASH_SIZE]>,
+}
+
+impl CounterState {
+    pub fn new<H: HashChain>(
+        previous: Option<&CounterState>,
+        counter: u64,
+        private_key: &[u8],
+    ) -> Self {
+        let previous_fingerprint = match previous {
+            Some(previous) => previous.fingerprint,
+            None => ArrayVec::from_array_len([0u8; MAX_HASH_SIZE], H::OUTPUT_SIZE as usize),
+        };
+        let fingerprint = compute_fingerprint::<H>(&previous_fingerprint, private_key);
+
+        Self {
+            counter,
+            previous_fingerprint,
+            fingerprint,
+        }
+    }
+
+    fn is_state_of<H: HashChain>(&self, private_key: &[u8]) -> bool {
+        compute_fingerprint::<H>(&self.previous_fingerprint, private_key)
+            .ct_eq(&self.fingerprint)
+            .into()
+    }
+
+    pub fn to_bytes(&self) -> ArrayVec<[u8; MAX_COUNTER_STATE_SIZE]> {
+        let mut result = ArrayVec::new();
+
+        result.extend_from_slice(&self.counter.to_be_bytes());
+        result.extend_from_slice(&self.previous_fingerprint);
+        result.extend_from_slice(&self.fingerprint);
+
+        result
+    }
+
+    /// Parse the state of a key with the hash function `H`, i.e. with `H::OUTPUT_SIZE` fingerprints
+    pub fn from_bytes<H: HashChain>(bytes: &[u8]) -> Result<Self, Error> {
+        if bytes.len() != 8 + 2 * H::OUTPUT_SIZE as usize {
+            return Err(Error::new());
+        }
+        let (counter, fingerprints) = bytes.split_at(8);
+        let (previous_fingerprint, fingerprint) = fingerprints.split_at(H::OUTPUT_SIZE as usize);
+
+        Ok(Self {
+            counter: u64::from_be_bytes(counter.try_into().unwrap()),
+            previous_fingerprint: previous_fingerprint.iter().copied().collect(),
+            fingerprint: fingerprint.iter().copied().collect(),
+        })
+    }
+}
+
+fn compute_fingerprint<H: HashChain>(
+    previous_fingerprint: &[u8],
+    private_key: &[u8],
+) -> ArrayVec<[u8; MAX_HASH_SIZE]> {
+    let mut prefix = [0u8; STATE_PREFIX_LEN];
+
+    prefix[STATE_D] = (D_STATE >> 8) as u8;
+    prefix[STATE_D + 1] = (D_STATE & 0xff) as u8;
+
+    H::default()
+        .chain(prefix)
+        .chain(previous_fingerprint)
+        .chain(private_key)
+        .finalize()
+}
+
+/**
+ * Storage for the state of a signing key that can't be rolled back together with the key, e.g.
+ * a hardware counter or a file on another volume than the key. One counter is used per signing
+ * key, i.e. each shard and backup needs its own counter.
+ *
+ * The counter is opt-in: only [`crate::SigningKey::from_bytes_with_counter`],
+ * [`crate::SigningKey::check_counter`] and [`crate::SigningKey::try_sign_with_counter`] consult
+ * it. Signatures created in any other way, e.g. with [`crate::sign`], [`crate::begin_sign`],
+ * [`signature::SignerMut`] or [`crate::SigningKeypair`], neither check nor advance the counter.
+ * A key used that way is only protected against rollbacks to states before its last commit.
+ */
+pub trait MonotonicCounter {
+    /// The last stored state, `None` if no state was stored yet
+    fn load(&mut self) -> Result<Option<CounterState>, Error>;
+
+    /// Store `state`, failing if its counter is lower than the one of the last stored state
+    fn store(&mut self, state: &CounterState) -> Result<(), Error>;
+}
+
+/**
+ * [`MonotonicCounter`] in memory, which only detects rollbacks during the lifetime of the
+ * process, e.g. a signing key reloaded from an outdated copy.
+ */
+#[derive(Clone, Debug, Default)]
+pub struct InMemoryCounter {
+    state: Option<CounterState>,
+}
+
+impl InMemoryCounter {
+    pub fn state(&self) -> Option<&CounterState> {
+        self.state.as_ref()
+    }
+}
+
+impl MonotonicCounter for InMemoryCounter {
+    fn load(&mut self) -> Result<Option<CounterState>, Error> {
+        Ok(self.state.clone())
+    }
+
+    fn store(&mut self, state: &CounterState) -> Result<(), Error> {
+        if matches!(&self.state, Some(current) if current.counter > state.counter) {
+            return Err(Error::new());
+        }
+        self.state = Some(state.clone());
+        Ok(())
+    }
+}
+
+/**
+ * [`MonotonicCounter`] stored in a file, which is replaced atomically on each update. The file
+ * and, on Unix, its directory are synced before [`MonotonicCounter::store`] returns.
+ */
+#[cfg(feature = "std")]
+#[derive(Clone, Debug)]
+pub struct FileCounter<H: HashChain> {
+    path: PathBuf,
+    phantom_data: PhantomData<H>,
+}
+
+#[cfg(feature = "std")]
+impl<H: HashChain> FileCounter<H> {
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self {
+            path: path.into(),
+            phantom_data: PhantomData,
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl<H: HashChain> MonotonicCounter for FileCounter<H> {
+    fn load(&mut self) -> Result<Option<CounterState>, Error> {
+        match fs::read(&self.path) {
+            Ok(bytes) => CounterState::from_bytes::<H>(&bytes).map(Some),
+            Err(error) if error.kind() == io::ErrorKind::NotFound => Ok(None),
+            Err(_) => Err(Error::new()),
+        }
+    }
+
+    fn store(&mut self, state: &CounterState) -> Result<(), Error> {
+        if matches!(self.load()?, Some(current) if current.counter > state.counter) {
+            return Err(Error::new());
+        }
+
+        let mut temporary_path = self.path.clone().into_os_string();
+        temporary_path.push(".tmp");
+
+        let mut file = fs::File::create(&temporary_path).map_err(|_| Error::new())?;
+        file.write_all(&state.to_bytes())
+            .and_then(|_| file.sync_all())
+            .map_err(|_| Error::new())?;
+        fs::rename(&temporary_path, &self.path).map_err(|_| Error::new())?;
+
+        sync_directory(&self.path).map_err(|_| Error::new())
+    }
+}
+
+/// Persist the directory entry of `path`, e.g. after a rename
+#[cfg(all(feature = "std", unix))]
+fn sync_directory(path: &Path) -> io::Result<()> {
+    let directory = match path.parent() {
+        Some(parent) if !parent.as_os_str().is_empty() => parent,
+        _ => Path::new("."),
+    };
+
+    fs::File::open(directory)?.sync_all()
+}
+
+/// Directories can't be opened and synced on other platforms
+#[cfg(all(feature = "std", not(unix)))]
+fn sync_directory(_path: &Path) -> io::Result<()> {
+    Ok(())
+}
+
+/**
+ * Reason why a signing key was refused by [`crate::SigningKey::check_counter`].
+ */
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum RollbackError {
+    /// The signing key could not be parsed
+    InvalidSigningKey,
+    /// The monotonic counter could not be loaded or stored
+    CounterUnavailable,
+    /// The signing key is behind the monotonic counter, e.g. restored from a snapshot
+    RolledBack { key: u64, counter: u64 },
+    /// The signing key is at the counter of the monotonic counter, but its state differs from the
+    /// recorded one, e.g. a copy of the key was used elsewhere
+    StateMismatch,
+}
+
+impl core::fmt::Display for RollbackError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            RollbackError::InvalidSigningKey => f.write_str("invalid signing key"),
+            RollbackError::CounterUnavailable => f.write_str("monotonic counter is unavailable"),
+            RollbackError::RolledBack { key, counter } => write!(
+                f,
+                "signing key at {} is behind the monotonic counter at {}",
+                key, counter
+            ),
+            RollbackError::StateMismatch => {
+                f.write_str("signing key differs from the recorded state")
+            }
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for RollbackError {}
+
+impl From<RollbackError> for Error {
+    fn from(_: RollbackError) -> Self {
+        Error::new()
+    }
+}
+
+/// Count of signature indices used by the encoded `private_key`
+pub fn used_signatures<H: HashChain>(private_key: &[u8]) -> Result<u64, RollbackError> {
+    ReferenceImplPrivateKey::<H>::from_binary_representation(private_key)
+        .and_then(|rfc_sk| rfc_sk.remaining_signatures())
+        .map(|remaining| remaining.start)
+        .map_err(|_| RollbackError::InvalidSigningKey)
+}
+
+pub fn check_counter<H: HashChain>(
+    private_key: &[u8],
+    counter: &mut impl MonotonicCounter,
+) -> Result<(), RollbackError> {
+    let used_signatures = used_signatures::<H>(private_key)?;
+
+    match counter
+        .load()
+        .map_err(|_| RollbackError::CounterUnavailable)?
+    {
+        Some(state) if state.fingerprint.len() != H::OUTPUT_SIZE as usize => {
+            Err(RollbackError::CounterUnavailable)
+        }
+        Some(state) if used_signatures < state.counter => Err(RollbackError::RolledBack {
+            key: used_signatures,
+            counter: state.counter,
+        }),
+        Some(state) if used_signatures == state.counter && !state.is_state_of::<H>(private_key) => {
+            Err(RollbackError::StateMismatch)
+        }
+        _ => Ok(()),
+    }
+}
+
+pub fn commit_counter<H: HashChain>(
+    used_signatures: u64,
+    private_key: &[u8],
+    counter: &mut impl MonotonicCounter,
+) -> Result<(), RollbackError> {
+    let previous = counter
+        .load()
+        .map_err(|_| RollbackError::CounterUnavailable)?;
+
+    counter
+        .store(&CounterState::new::<H>(
+            previous.as_ref(),
+            used_signatures,
+            private_key,
+        ))
+        .map_err(|_| RollbackError::CounterUnavailable)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        hss::{hss_keygen, SigningKey},
+        util::helper::test_helper::gen_random_seed,
+        HssParameter, LmotsAlgorithm, LmsAlgorithm, Sha256_256,
+    };
+
+    use signature::{SignerMut, Verifier};
+
+    type H = Sha256_256;
+
+    fn parameters() -> [HssParameter<H>; 2] {
+        [
+            HssParameter::new(LmotsAlgorithm::LmotsW4, LmsAlgorithm::LmsH5),
+            HssParameter::new(LmotsAlgorithm::LmotsW4, LmsAlgorithm::LmsH5),
+        ]
+    }
+
+    #[test]
+    fn detect_rollback() {
+        let message = [1u8, 2, 3];
+        let mut counter = InMemoryCounter::default();
+
+        let (mut signing_key, verifying_key) =
+            hss_keygen::<H>(&parameters(), &gen_random_seed::<H>(), None).unwrap();
+        signing_key.commit_counter(&mut counter).unwrap();
+        assert_eq!(counter.state().unwrap().counter, 0);
+
+        let signature = signing_key
+            .try_sign_with_counter(&message, &mut counter)
+            .unwrap();
+        assert!(verifying_key.verify(&message, &signature).is_ok());
+        let snapshot = signing_key.duplicate();
+
+        signing_key
+            .try_sign_with_counter(&message, &mut counter)
+            .unwrap();
+        let state = counter.state().unwrap().clone();
+        assert_eq!(state.counter, 2);
+        assert_eq!(
+            CounterState::from_bytes::<H>(&state.to_bytes()).unwrap(),
+            state
+        );
+        // The fingerprints have the length of the hash function
+        assert!(CounterState::from_bytes::<H>(&state.to_bytes()[..8]).is_err());
+        assert!(CounterState::from_bytes::<H>(&state.to_bytes()[..8 + 2 * 24]).is_err());
+
+        assert_eq!(
+            SigningKey::<H>::from_bytes_with_counter(snapshot.expose_secret(), &mut counter),
+            Err(RollbackError::RolledBack { key: 1, counter: 2 })
+        );
+        let mut snapshot = snapshot;
+        assert!(snapshot
+            .try_sign_with_counter(&message, &mut counter)
+            .is_err());
+        assert_eq!(snapshot.signature_range().unwrap().start, 1);
+
+        let mut signing_key =
+            SigningKey::<H>::from_bytes_with_counter(signing_key.expose_secret(), &mut counter)
+                .unwrap();
+
+        // Changed without signing, hence the state has to be committed again
+        let _backup = signing_key.split_backup(32, 0).unwrap();
+        assert_eq!(
+            signing_key.check_counter(&mut counter),
+            Err(RollbackError::StateMismatch)
+        );
+        signing_key.commit_counter(&mut counter).unwrap();
+        assert_eq!(
+            counter.state().unwrap().previous_fingerprint,
+            state.fingerprint
+        );
+        assert!(signing_key
+            .try_sign_with_counter(&message, &mut counter)
+            .is_ok());
+
+        assert!(counter.store(&state).is_err());
+    }
+
+    #[test]
+    fn counter_is_opt_in() {
+        let message = [1u8, 2, 3];
+        let mut counter = InMemoryCounter::default();
+
+        let (mut signing_key, verifying_key) =
+            hss_keygen::<H>(&parameters(), &gen_random_seed::<H>(), None).unwrap();
+        signing_key.commit_counter(&mut counter).unwrap();
+        let snapshot = signing_key.duplicate();
+
+        // Signing without the counter neither checks nor advances it
+        let signature = signing_key.try_sign(&message).unwrap();
+        assert!(verifying_key.verify(&message, &signature).is_ok());
+        assert_eq!(counter.state().unwrap().counter, 0);
+        assert!(signing_key.check_counter(&mut counter).is_ok());
+
+        // Hence, a rollback to the last committed state is not detected
+        assert!(snapshot.check_counter(&mut counter).is_ok());
+
+        // Until the advanced key is committed
+        signing_key.commit_counter(&mut counter).unwrap();
+        assert_eq!(
+            snapshot.check_counter(&mut counter),
+            Err(RollbackError::RolledBack { key: 0, counter: 1 })
+        );
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn file_counter() {
+        let message = [1u8, 2, 3];
+        let directory = tempfile::tempdir().unwrap();
+        let path = directory.path().join("counter");
+
+        let (mut signing_key, _) =
+            hss_keygen::<H>(&parameters(), &gen_random_seed::<H>(), None).unwrap();
+        let snapshot = signing_key.duplicate();
+
+        let mut counter = FileCounter::<H>::new(&path);
+        assert_eq!(counter.load().unwrap(), None);
+        signing_key
+            .try_sign_with_counter(&message, &mut counter)
+            .unwrap();
+
+        // The state survives the counter instance
+        let mut counter = FileCounter::<H>::new(&path);
+        assert_eq!(counter.load().unwrap().unwrap().counter, 1);
+        assert_eq!(
+            snapshot.check_counter(&mut counter),
+            Err(RollbackError::RolledBack { key: 0, counter: 1 })
+        );
+        assert!(signing_key.check_counter(&mut counter).is_ok());
+
+        std::fs::write(&path, [0u8; 8]).unwrap();
+        assert_eq!(
+            signing_key.check_counter(&mut counter),
+            Err(RollbackError::CounterUnavailable)
+        );
+    }
+}
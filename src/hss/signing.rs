@@ -1,24 +1,30 @@
 use crate::{
     constants::{
-        lms_public_key_length, lms_signature_length, MAX_ALLOWED_HSS_LEVELS,
-        MAX_HSS_SIGNATURE_LENGTH, MAX_HSS_SIGNED_PUBLIC_KEY_LENGTH,
+        lms_public_key_length, lms_signature_length, MAX_ALLOWED_HSS_LEVELS, MAX_HASH_SIZE,
+        MAX_HSS_SIGNATURE_LENGTH, MAX_HSS_SIGNED_PUBLIC_KEY_LENGTH, PREHASH_PREFIX_LEN,
     },
+    hasher::HashOperations,
     hss::{
         aux::MutableExpandedAuxData,
         reference_impl_private_key::{generate_signature_randomizer, SeedAndLmsTreeIdentifier},
     },
+    lm_ots::signing::message_hash_len,
     lms::{
-        self,
-        definitions::{InMemoryLmsPublicKey, LmsPublicKey},
-        signing::{InMemoryLmsSignature, LmsSignature},
+        definitions::{InMemoryLmsPublicKey, LmsPrivateKey, LmsPublicKey},
+        signing::{InMemoryLmsSignature, LmsSignature, PreparedLmsSignature},
     },
     util::helper::read_and_advance,
-    HashChain,
+    HashChain, Signature,
 };
 
-use super::definitions::HssPrivateKey;
+use super::{definitions::HssPrivateKey, is_prehash_message};
+
+#[cfg(feature = "fast_verify_core")]
+use {crate::lm_ots::signing::FastVerifyOptions, rand_core::CryptoRngCore};
 
 use core::convert::TryInto;
+use digest::Update;
+use signature::Error;
 use tinyvec::ArrayVec;
 
 #[derive(PartialEq)]
@@ -31,9 +37,64 @@ pub struct HssSignature<H: HashChain> {
 impl<H: HashChain> HssSignature<H> {
     pub fn sign(
         private_key: &mut HssPrivateKey<H>,
-        message: Option<&[u8]>,
-        message_mut: Option<&mut [u8]>,
+        message: &[u8],
+        aux_data: &mut Option<MutableExpandedAuxData>,
+        hash_operations: &mut HashOperations,
+    ) -> Result<HssSignature<H>, ()> {
+        HssSignature::sign_core(
+            private_key,
+            aux_data,
+            hash_operations,
+            |lms_private_key, signature_randomizer, aux_data, hash_operations| {
+                LmsSignature::sign(
+                    lms_private_key,
+                    message,
+                    &signature_randomizer,
+                    aux_data,
+                    hash_operations,
+                )
+            },
+        )
+    }
+
+    #[cfg(feature = "fast_verify_core")]
+    pub fn sign_fast_verify(
+        private_key: &mut HssPrivateKey<H>,
+        message_mut: &mut [u8],
+        fast_verify_options: &FastVerifyOptions,
+        rng: &mut dyn CryptoRngCore,
+        aux_data: &mut Option<MutableExpandedAuxData>,
+        hash_operations: &mut HashOperations,
+    ) -> Result<HssSignature<H>, ()> {
+        HssSignature::sign_core(
+            private_key,
+            aux_data,
+            hash_operations,
+            |lms_private_key, mut signature_randomizer, aux_data, hash_operations| {
+                LmsSignature::sign_fast_verify(
+                    lms_private_key,
+                    None,
+                    Some(message_mut),
+                    &mut signature_randomizer,
+                    fast_verify_options,
+                    rng,
+                    aux_data,
+                    hash_operations,
+                )
+            },
+        )
+    }
+
+    fn sign_core(
+        private_key: &mut HssPrivateKey<H>,
         aux_data: &mut Option<MutableExpandedAuxData>,
+        hash_operations: &mut HashOperations,
+        sign_lms: impl FnOnce(
+            &mut LmsPrivateKey<H>,
+            ArrayVec<[u8; MAX_HASH_SIZE]>,
+            &mut Option<MutableExpandedAuxData>,
+            &mut HashOperations,
+        ) -> Result<LmsSignature<H>, ()>,
     ) -> Result<HssSignature<H>, ()> {
         let max_level = private_key.get_length();
 
@@ -47,49 +108,67 @@ impl<H: HashChain> HssSignature<H> {
         }
 
         // Sign the message
-        #[allow(unused_mut)]
-        let mut signature_randomizer = generate_signature_randomizer::<H>(
+        let signature_randomizer = generate_signature_randomizer::<H>(
             &SeedAndLmsTreeIdentifier {
                 seed: prv[max_level - 1].seed.clone(),
                 lms_tree_identifier: prv[max_level - 1].lms_tree_identifier,
             },
             &prv[max_level - 1].used_leafs_index,
+            hash_operations,
         );
-        let new_signature = if cfg!(feature = "fast_verify") && message_mut.is_some() {
-            #[cfg(feature = "fast_verify")]
-            let lms_sig = lms::signing::LmsSignature::sign_fast_verify(
-                &mut prv[max_level - 1],
-                None,
-                message_mut,
-                &mut signature_randomizer,
-                aux_data,
-            );
-            #[cfg(not(feature = "fast_verify"))]
-            let lms_sig = Err(());
-            lms_sig
-        } else {
-            lms::signing::LmsSignature::sign(
-                &mut prv[max_level - 1],
-                message.unwrap(),
-                &signature_randomizer,
-                aux_data,
-            )
-        }?;
+        let new_signature = sign_lms(
+            &mut prv[max_level - 1],
+            signature_randomizer,
+            aux_data,
+            hash_operations,
+        )?;
         sig.push(new_signature);
 
         // Create list of signed keys
-        let mut signed_public_keys = ArrayVec::new();
+        let mut signed_public_keys: ArrayVec<[_; MAX_ALLOWED_HSS_LEVELS - 1]> =
+            private_key.delegation.iter().cloned().collect();
         for i in 0..max_level - 1 {
             signed_public_keys.push(HssSignedPublicKey::new(sig[i].clone(), public[i].clone()));
         }
 
         Ok(HssSignature {
-            level: max_level - 1,
+            level: signed_public_keys.len(),
             signed_public_keys,
             signature: sig[max_level - 1].clone(),
         })
     }
 
+    pub fn to_signature(&self, _hash_operations: HashOperations) -> Result<Signature, Error> {
+        let verify_chain_steps = self
+            .signed_public_keys
+            .iter()
+            .map(|signed_public_key| signed_public_key.sig.lmots_signature.verify_chain_steps())
+            .chain(Some(self.signature.lmots_signature.verify_chain_steps()))
+            .map(u32::from)
+            .sum();
+
+        let signature = Signature::from_bytes_with_chain_steps(
+            &self.to_binary_representation(),
+            Some(verify_chain_steps),
+        )?;
+
+        #[cfg(feature = "verbose")]
+        let signature = {
+            let mut signature = signature;
+            signature.hash_iterations = self
+                .signed_public_keys
+                .iter()
+                .map(|signed_public_key| signed_public_key.sig.lmots_signature.hash_iterations)
+                .chain(Some(self.signature.lmots_signature.hash_iterations))
+                .map(u32::from)
+                .sum();
+            signature.hash_operations = _hash_operations;
+            signature
+        };
+
+        Ok(signature)
+    }
+
     pub fn to_binary_representation(&self) -> ArrayVec<[u8; MAX_HSS_SIGNATURE_LENGTH]> {
         let mut result = ArrayVec::new();
 
@@ -106,6 +185,107 @@ impl<H: HashChain> HssSignature<H> {
     }
 }
 
+/**
+ * Generates a signature over a message that is fed incrementally using [`Update`].
+ *
+ * The leaf used for the signature is already reserved and the private key advanced, when the
+ * [`StreamSigner`] is created. Hence, the message can be hashed while it is streamed and
+ * [`StreamSigner::finalize`] only computes the LM-OTS signature. Created by
+ * [`crate::SigningKey::begin_sign`] or [`crate::begin_sign`].
+ */
+pub struct StreamSigner<H: HashChain> {
+    signed_public_keys: ArrayVec<[HssSignedPublicKey<H>; MAX_ALLOWED_HSS_LEVELS - 1]>,
+    signature: PreparedLmsSignature<H>,
+    signature_randomizer: ArrayVec<[u8; MAX_HASH_SIZE]>,
+    message_hasher: H,
+    message_len: usize,
+    message_prefix: ArrayVec<[u8; PREHASH_PREFIX_LEN]>,
+    hash_operations: HashOperations,
+}
+
+impl<H: HashChain> StreamSigner<H> {
+    pub(crate) fn new(
+        private_key: &mut HssPrivateKey<H>,
+        aux_data: &mut Option<MutableExpandedAuxData>,
+        hash_operations: &mut HashOperations,
+    ) -> Result<Self, ()> {
+        let max_level = private_key.get_length();
+
+        if private_key.signatures.get(max_level - 1).is_some() {
+            return Err(());
+        }
+
+        let lms_private_key = &mut private_key.private_key[max_level - 1];
+        let signature_randomizer = generate_signature_randomizer::<H>(
+            &SeedAndLmsTreeIdentifier {
+                seed: lms_private_key.seed.clone(),
+                lms_tree_identifier: lms_private_key.lms_tree_identifier,
+            },
+            &lms_private_key.used_leafs_index,
+            hash_operations,
+        );
+        let signature = LmsSignature::prepare(lms_private_key, aux_data, hash_operations)?;
+
+        let mut signed_public_keys: ArrayVec<[_; MAX_ALLOWED_HSS_LEVELS - 1]> =
+            private_key.delegation.iter().cloned().collect();
+        for i in 0..max_level - 1 {
+            signed_public_keys.push(HssSignedPublicKey::new(
+                private_key.signatures[i].clone(),
+                private_key.public_key[i].clone(),
+            ));
+        }
+
+        let message_hasher = signature.message_hasher(&signature_randomizer);
+
+        Ok(Self {
+            signed_public_keys,
+            signature,
+            signature_randomizer,
+            message_hasher,
+            message_len: 0,
+            message_prefix: ArrayVec::new(),
+            hash_operations: *hash_operations,
+        })
+    }
+
+    pub fn finalize(mut self) -> Result<Signature, Error> {
+        if is_prehash_message(&self.message_prefix) {
+            return Err(Error::new());
+        }
+
+        self.hash_operations
+            .add_message(HashOperations::compressions::<H>(message_hash_len::<H>(
+                self.message_len,
+            )));
+
+        let signature = self.signature.finalize(
+            &mut self.message_hasher,
+            &self.signature_randomizer,
+            &mut self.hash_operations,
+        );
+
+        HssSignature {
+            level: self.signed_public_keys.len(),
+            signed_public_keys: self.signed_public_keys,
+            signature,
+        }
+        .to_signature(self.hash_operations)
+    }
+}
+
+impl<H: HashChain> Update for StreamSigner<H> {
+    fn update(&mut self, data: &[u8]) {
+        self.message_hasher.update(data);
+        self.message_len += data.len();
+
+        let prefix_len = self.message_prefix.len();
+        if prefix_len < PREHASH_PREFIX_LEN {
+            let missing = (PREHASH_PREFIX_LEN - prefix_len).min(data.len());
+            self.message_prefix.extend_from_slice(&data[..missing]);
+        }
+    }
+}
+
 /// To reduce memory footprint on verification we handle the signature in-memory using ```InMemoryHssSignature```.
 /// In order to reduce complexity we use ```HssSignature``` for key generation and signature generation.
 pub struct InMemoryHssSignature<'a, H: HashChain> {
@@ -156,10 +336,7 @@ impl<'a, H: HashChain> InMemoryHssSignature<'a, H> {
             signed_public_keys.push(Some(signed_public_key));
         }
 
-        let signature = match InMemoryLmsSignature::<'a, H>::new(&data[index..]) {
-            None => return None,
-            Some(x) => x,
-        };
+        let signature = InMemoryLmsSignature::<'a, H>::new(&data[index..])?;
 
         Some(Self {
             level,
@@ -169,7 +346,7 @@ impl<'a, H: HashChain> InMemoryHssSignature<'a, H> {
     }
 }
 
-#[derive(Default, Clone, PartialEq, Eq)]
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
 pub struct HssSignedPublicKey<H: HashChain> {
     pub sig: LmsSignature<H>,
     pub public_key: LmsPublicKey<H>,
@@ -207,10 +384,7 @@ impl<H: HashChain> HssSignedPublicKey<H> {
 
 impl<'a, H: HashChain> InMemoryHssSignedPublicKey<'a, H> {
     pub fn new(data: &'a [u8]) -> Option<Self> {
-        let sig = match InMemoryLmsSignature::new(data) {
-            None => return None,
-            Some(x) => x,
-        };
+        let sig = InMemoryLmsSignature::new(data)?;
 
         let sig_size = lms_signature_length(
             sig.lmots_signature
@@ -220,10 +394,7 @@ impl<'a, H: HashChain> InMemoryHssSignedPublicKey<'a, H> {
             sig.lms_parameter.get_tree_height() as usize,
         );
 
-        let public_key = match InMemoryLmsPublicKey::new(&data[sig_size..]) {
-            None => return None,
-            Some(x) => x,
-        };
+        let public_key = InMemoryLmsPublicKey::new(&data[sig_size..])?;
 
         Some(Self { sig, public_key })
     }
@@ -281,15 +452,26 @@ mod tests {
         )
         .unwrap();
 
-        let mut private_key = HssPrivateKey::from(&private_key, &mut None).unwrap();
+        let mut private_key =
+            HssPrivateKey::from(&private_key, &mut None, &mut Default::default()).unwrap();
 
         let message = [2, 56, 123, 22, 42, 49, 22];
 
-        let _ = HssSignature::sign(&mut private_key, Some(&message), None, &mut None)
-            .expect("Should generate HSS signature");
+        let _ = HssSignature::sign(
+            &mut private_key,
+            &message,
+            &mut None,
+            &mut Default::default(),
+        )
+        .expect("Should generate HSS signature");
 
-        let _ = HssSignature::sign(&mut private_key, Some(&message), None, &mut None)
-            .expect("Signing should panic!");
+        let _ = HssSignature::sign(
+            &mut private_key,
+            &message,
+            &mut None,
+            &mut Default::default(),
+        )
+        .expect("Signing should panic!");
     }
 
     #[test]
@@ -303,6 +485,7 @@ mod tests {
             &HssParameter::construct_default_parameters(),
             &0,
             &mut None,
+            &mut Default::default(),
         );
 
         let message = [3, 54, 32, 45, 67, 32, 12, 58, 29, 49];
@@ -314,6 +497,7 @@ mod tests {
             &message,
             &signature_randomizer,
             &mut None,
+            &mut Default::default(),
         )
         .expect("Signing should work");
 
@@ -341,14 +525,20 @@ mod tests {
         )
         .unwrap();
 
-        let mut private_key = HssPrivateKey::from(&private_key, &mut None).unwrap();
+        let mut private_key =
+            HssPrivateKey::from(&private_key, &mut None, &mut Default::default()).unwrap();
 
         let message_values = [2, 56, 123, 22, 42, 49, 22];
         let mut message = [0u8; 64];
         message[..message_values.len()].copy_from_slice(&message_values);
 
-        let signature = HssSignature::sign(&mut private_key, Some(&message), None, &mut None)
-            .expect("Should generate HSS signature");
+        let signature = HssSignature::sign(
+            &mut private_key,
+            &message,
+            &mut None,
+            &mut Default::default(),
+        )
+        .expect("Should generate HSS signature");
 
         let binary_representation = signature.to_binary_representation();
         let deserialized = InMemoryHssSignature::<Hasher>::new(binary_representation.as_slice())
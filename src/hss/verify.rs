@@ -1,15 +1,133 @@
+use core::marker::PhantomData;
+
+use digest::Update;
+use signature::Error;
+use tinyvec::ArrayVec;
+
 use crate::{
-    hasher::HashChain,
-    lms::{self},
+    constants::MAX_LMS_PUBLIC_KEY_LENGTH,
+    hasher::{HashChain, HashOperations},
+    lm_ots,
+    lms::{self, definitions::InMemoryLmsPublicKey, signing::InMemoryLmsSignature},
 };
 
 use super::{definitions::InMemoryHssPublicKey, signing::InMemoryHssSignature};
 
-pub fn verify<'a, H: HashChain>(
+#[derive(Default)]
+struct CachedPublicKeyPair {
+    parent: ArrayVec<[u8; MAX_LMS_PUBLIC_KEY_LENGTH]>,
+    child: ArrayVec<[u8; MAX_LMS_PUBLIC_KEY_LENGTH]>,
+}
+
+/**
+ * Remembers up to `N` already verified signed public keys of lower HSS levels.
+ *
+ * A (parent public key, child public key) pair is only stored after the LMS signature of the
+ * parent over the child has been verified. Subsequent verifications with the same cache skip
+ * the signatures of stored pairs, so that for signatures of the same subtree only the bottom
+ * level signature is checked. If the cache is full, the oldest pair is replaced.
+ */
+pub struct VerifierCache<H: HashChain, const N: usize> {
+    pairs: ArrayVec<[CachedPublicKeyPair; N]>,
+    next_replaced: usize,
+    phantom_data: PhantomData<H>,
+}
+
+impl<H: HashChain, const N: usize> Default for VerifierCache<H, N> {
+    fn default() -> Self {
+        Self {
+            pairs: ArrayVec::new(),
+            next_replaced: 0,
+            phantom_data: PhantomData,
+        }
+    }
+}
+
+impl<H: HashChain, const N: usize> VerifierCache<H, N> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn len(&self) -> usize {
+        self.pairs.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.pairs.is_empty()
+    }
+
+    pub fn clear(&mut self) {
+        self.pairs.clear();
+        self.next_replaced = 0;
+    }
+
+    fn contains(&self, parent: &[u8], child: &[u8]) -> bool {
+        self.pairs
+            .iter()
+            .any(|pair| pair.parent.as_slice() == parent && pair.child.as_slice() == child)
+    }
+
+    fn insert(&mut self, parent: &[u8], child: &[u8]) {
+        if N == 0 {
+            return;
+        }
+
+        let mut pair = CachedPublicKeyPair::default();
+        pair.parent.extend_from_slice(parent);
+        pair.child.extend_from_slice(child);
+
+        if self.pairs.len() < N {
+            self.pairs.push(pair);
+        } else {
+            self.pairs[self.next_replaced] = pair;
+            self.next_replaced = (self.next_replaced + 1) % N;
+        }
+    }
+}
+
+/**
+ * Hashes a single signature verification took, see [`crate::verify_with_cost`].
+ *
+ * Signed public keys skipped thanks to a [`VerifierCache`] do not add to the cost.
+ */
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct VerificationCost {
+    /// Steps of the LM-OTS Winternitz chains, the complement of the hash iterations of the signer
+    pub chain_steps: u32,
+    /// LMS leaf and interior node hashes of the authentication paths
+    pub tree_hashes: u32,
+}
+
+impl VerificationCost {
+    fn add<H: HashChain>(&mut self, signature: &InMemoryLmsSignature<'_, H>, chain_steps: u32) {
+        self.chain_steps += chain_steps;
+        self.tree_hashes += 1 + signature.lms_parameter.get_tree_height() as u32;
+    }
+}
+
+pub fn verify<'a, H: HashChain, const N: usize>(
     signature: &InMemoryHssSignature<'a, H>,
     public_key: &InMemoryHssPublicKey<'a, H>,
     message: &[u8],
+    cache: &mut VerifierCache<H, N>,
+    cost: &mut VerificationCost,
+    hash_operations: &mut HashOperations,
 ) -> Result<(), ()> {
+    let key = verify_signed_public_keys(signature, public_key, cache, cost, hash_operations)?;
+
+    let chain_steps = lms::verify::verify(&signature.signature, key, message, hash_operations)?;
+    cost.add(&signature.signature, chain_steps);
+    Ok(())
+}
+
+/// Verifies the chain of signed public keys and returns the public key of the bottom level tree
+fn verify_signed_public_keys<'a, 'b, H: HashChain, const N: usize>(
+    signature: &'b InMemoryHssSignature<'a, H>,
+    public_key: &'b InMemoryHssPublicKey<'a, H>,
+    cache: &mut VerifierCache<H, N>,
+    cost: &mut VerificationCost,
+    hash_operations: &mut HashOperations,
+) -> Result<&'b InMemoryLmsPublicKey<'a, H>, ()> {
     if signature.level + 1 != public_key.level {
         return Err(());
     }
@@ -19,13 +137,85 @@ pub fn verify<'a, H: HashChain>(
         let sig = &signature.signed_public_keys[i].as_ref().unwrap().sig;
         let msg = &signature.signed_public_keys[i].as_ref().unwrap().public_key;
 
-        if lms::verify::verify(sig, key, msg.as_slice()).is_err() {
-            return Err(());
+        if !cache.contains(key.as_slice(), msg.as_slice()) {
+            let chain_steps = lms::verify::verify(sig, key, msg.as_slice(), hash_operations)?;
+            cost.add(sig, chain_steps);
+            cache.insert(key.as_slice(), msg.as_slice());
         }
         key = msg;
     }
 
-    lms::verify::verify(&signature.signature, key, message)
+    Ok(key)
+}
+
+/**
+ * Verifies a signature over a message that is fed incrementally using [`Update`].
+ *
+ * Only `I || q || D_MESG || C || message` depends on the message, so the message is hashed while
+ * it is streamed. [`StreamVerifier::finalize`] then checks the hash chains, the authentication
+ * paths and the signed public keys. Created by [`crate::VerifyingKey::begin_verify`].
+ */
+pub struct StreamVerifier<'a, H: HashChain> {
+    signature: InMemoryHssSignature<'a, H>,
+    public_key: InMemoryHssPublicKey<'a, H>,
+    message_hasher: H,
+}
+
+impl<'a, H: HashChain> StreamVerifier<'a, H> {
+    pub(crate) fn new(
+        signature: InMemoryHssSignature<'a, H>,
+        public_key: InMemoryHssPublicKey<'a, H>,
+    ) -> Self {
+        let bottom_key = match signature.level.checked_sub(1) {
+            Some(level) => {
+                &signature.signed_public_keys[level]
+                    .as_ref()
+                    .unwrap()
+                    .public_key
+            }
+            None => &public_key.public_key,
+        };
+        let message_hasher = lm_ots::verify::message_hasher(
+            &signature.signature.lmots_signature,
+            bottom_key.lms_tree_identifier,
+            signature.signature.lms_leaf_identifier,
+        );
+
+        Self {
+            signature,
+            public_key,
+            message_hasher,
+        }
+    }
+
+    pub fn finalize(self) -> Result<(), Error> {
+        let message_hash = self.message_hasher.finalize();
+        let mut hash_operations = HashOperations::default();
+
+        let key = verify_signed_public_keys(
+            &self.signature,
+            &self.public_key,
+            &mut VerifierCache::<H, 0>::new(),
+            &mut Default::default(),
+            &mut hash_operations,
+        )
+        .map_err(|_| Error::new())?;
+
+        lms::verify::verify_message_hash(
+            &self.signature.signature,
+            key,
+            &message_hash,
+            &mut hash_operations,
+        )
+        .map(|_| ())
+        .map_err(|_| Error::new())
+    }
+}
+
+impl<'a, H: HashChain> Update for StreamVerifier<'a, H> {
+    fn update(&mut self, data: &[u8]) {
+        self.message_hasher.update(data);
+    }
 }
 
 #[cfg(test)]
@@ -36,7 +226,7 @@ mod tests {
             definitions::{HssPrivateKey, HssPublicKey, InMemoryHssPublicKey},
             reference_impl_private_key::ReferenceImplPrivateKey,
             signing::{HssSignature, InMemoryHssSignature},
-            verify::verify,
+            verify::{verify, VerifierCache},
         },
         HssParameter,
     };
@@ -56,8 +246,9 @@ mod tests {
         )
         .unwrap();
 
-        let mut private_key = HssPrivateKey::from(&rfc_key, &mut None).unwrap();
-        let public_key = HssPublicKey::from(&rfc_key, None).unwrap();
+        let mut private_key =
+            HssPrivateKey::from(&rfc_key, &mut None, &mut Default::default()).unwrap();
+        let public_key = HssPublicKey::from(&rfc_key, None, &mut Default::default()).unwrap();
 
         let message_values = [42, 57, 20, 59, 33, 1, 49, 3, 99, 130, 50, 20];
 
@@ -71,13 +262,20 @@ mod tests {
         public_key: &HssPublicKey<H>,
         message: &mut [u8],
     ) {
-        let signature = if cfg!(feature = "fast_verify") {
-            HssSignature::sign(private_key, None, Some(message), &mut None)
-                .expect("Should sign message")
-        } else {
-            HssSignature::sign(private_key, Some(message), None, &mut None)
-                .expect("Should sign message")
-        };
+        #[cfg(feature = "fast_verify_core")]
+        let signature = HssSignature::sign_fast_verify(
+            private_key,
+            message,
+            &Default::default(),
+            &mut rand::rngs::OsRng,
+            &mut None,
+            &mut Default::default(),
+        )
+        .expect("Should sign message");
+        #[cfg(not(feature = "fast_verify_core"))]
+        let signature =
+            HssSignature::sign(private_key, message, &mut None, &mut Default::default())
+                .expect("Should sign message");
 
         let mem_sig = signature.to_binary_representation();
         let mem_sig = InMemoryHssSignature::<H>::new(mem_sig.as_slice()).unwrap();
@@ -85,10 +283,26 @@ mod tests {
         let mem_pub = public_key.to_binary_representation();
         let mem_pub = InMemoryHssPublicKey::new(mem_pub.as_slice()).unwrap();
 
-        assert!(verify(&mem_sig, &mem_pub, message).is_ok());
+        assert!(verify(
+            &mem_sig,
+            &mem_pub,
+            message,
+            &mut VerifierCache::<H, 0>::new(),
+            &mut Default::default(),
+            &mut Default::default(),
+        )
+        .is_ok());
 
         message[0] = !message[0];
 
-        assert!(verify(&mem_sig, &mem_pub, message).is_err());
+        assert!(verify(
+            &mem_sig,
+            &mem_pub,
+            message,
+            &mut VerifierCache::<H, 0>::new(),
+            &mut Default::default(),
+            &mut Default::default(),
+        )
+        .is_err());
     }
 }
@@ -9,6 +9,9 @@
 //! [NIST Recommendations for Stateful Hash-Based Signatures](https://doi.org/10.6028/NIST.SP.800-208)
 //!
 //! This crate can be used together with the [`signature::SignerMut`] and [`signature::Verifier`] traits.
+//! Messages hashed beforehand are supported by [`signature::DigestVerifier`] and, via
+//! [`SigningKeypair`], by [`signature::DigestSigner`] in a separate prehash mode, which is limited
+//! to the hash functions with 256 bit output (see [`SigningKey::try_sign_digest`]).
 //!
 //! # Example
 //! ```
@@ -81,6 +84,20 @@
 //!
 //! If the crate is compiled with the std library, the effort of the generation of fast verifiable
 //! signatures can be split to multiple threads using the `HBS_LMS_THREADS`.
+//!
+//! These values are only the defaults of [`FastVerifyOptions`], which allow to adapt the effort,
+//! the thread count and a deadline for each signature at runtime.
+//!
+//! The 'fast_verify_core' feature provides fast verifiable signatures without the std library.
+//! The randomness is then supplied by the caller (see [`sign_mut_with_rng`]) and all trials are
+//! computed on the calling thread.
+//!
+//! Key pairs can be generated from any `rand_core::CryptoRng` with `keygen_with_rng` by enabling
+//! the 'rand_core' feature (also enabled by 'fast_verify_core'), which works without the std
+//! library as well.
+//!
+//! Independent of these features, the signed public keys of the lower HSS levels can be made fast
+//! verifiable with [`HssParameter::with_fast_verify_signed_public_key`].
 
 extern crate core;
 
@@ -102,19 +119,53 @@ pub use crate::hss::reference_impl_private_key::Seed;
 pub use crate::hasher::{
     sha256::{Sha256_128, Sha256_192, Sha256_256},
     shake256::{Shake256_128, Shake256_192, Shake256_256},
-    HashChain, HashChainData,
+    FullWidthHashChain, HashChain, HashChainData,
 };
 
 pub use crate::hss::parameter::HssParameter;
 pub use crate::lm_ots::parameters::LmotsAlgorithm;
 pub use crate::lms::parameters::LmsAlgorithm;
 
+#[cfg(feature = "verbose")]
+pub use crate::hasher::HashOperations;
+
+#[cfg(feature = "std")]
+pub use crate::hss::audit_log::hss_verify_audit_log as verify_audit_log;
+pub use crate::hss::audit_log::{AuditLog, AuditLogEntry, AuditLogError};
+#[cfg(feature = "std")]
+pub use crate::hss::audit_log::{FileAuditLog, InMemoryAuditLog};
+pub use crate::hss::aux::hss_aux_data_info as aux_data_info;
+pub use crate::hss::aux::hss_aux_data_info_for_budget as aux_data_info_for_budget;
+pub use crate::hss::aux::hss_aux_data_info_for_level as aux_data_info_for_level;
+pub use crate::hss::aux::{AuxDataError, AuxDataInfo};
+
+pub use crate::hss::hd_seed::hss_derive_seed as derive_seed;
+pub use crate::hss::hss_begin_sign as begin_sign;
 pub use crate::hss::hss_keygen as keygen;
+#[cfg(feature = "verbose")]
+pub use crate::hss::hss_keygen_verbose as keygen_verbose;
+#[cfg(feature = "rand_core")]
+pub use crate::hss::hss_keygen_with_rng as keygen_with_rng;
 pub use crate::hss::hss_sign as sign;
 #[cfg(feature = "fast_verify")]
 pub use crate::hss::hss_sign_mut as sign_mut;
+#[cfg(feature = "fast_verify_core")]
+pub use crate::hss::hss_sign_mut_with_rng as sign_mut_with_rng;
 pub use crate::hss::hss_verify as verify;
-pub use crate::hss::{SigningKey, VerifyingKey};
+pub use crate::hss::hss_verify_batch as verify_batch;
+pub use crate::hss::hss_verify_cached as verify_cached;
+pub use crate::hss::hss_verify_cached_with_cost as verify_cached_with_cost;
+#[cfg(feature = "verbose")]
+pub use crate::hss::hss_verify_verbose as verify_verbose;
+pub use crate::hss::hss_verify_with_cost as verify_with_cost;
+#[cfg(feature = "std")]
+pub use crate::hss::rollback::FileCounter;
+pub use crate::hss::rollback::{CounterState, InMemoryCounter, MonotonicCounter, RollbackError};
+pub use crate::hss::signing::StreamSigner;
+pub use crate::hss::verify::{StreamVerifier, VerificationCost, VerifierCache};
+pub use crate::hss::{BackupKey, DelegatedSigningKey, SigningKey, SigningKeypair, VerifyingKey};
+#[cfg(feature = "fast_verify_core")]
+pub use crate::lm_ots::signing::FastVerifyOptions;
 
 use core::convert::TryFrom;
 use signature::Error;
@@ -128,18 +179,29 @@ use constants::MAX_HSS_SIGNATURE_LENGTH;
 #[derive(Debug)]
 pub struct Signature {
     bytes: ArrayVec<[u8; MAX_HSS_SIGNATURE_LENGTH]>,
+    /// Hash chain steps the verification of all LM-OTS signatures takes, if the signature was
+    /// generated by this crate instead of being parsed
+    pub verify_chain_steps: Option<u32>,
     #[cfg(feature = "verbose")]
     pub hash_iterations: u32,
+    #[cfg(feature = "verbose")]
+    pub hash_operations: HashOperations,
 }
 
 impl Signature {
-    pub(crate) fn from_bytes_verbose(bytes: &[u8], _hash_iterations: u32) -> Result<Self, Error> {
+    pub(crate) fn from_bytes_with_chain_steps(
+        bytes: &[u8],
+        verify_chain_steps: Option<u32>,
+    ) -> Result<Self, Error> {
         let bytes = ArrayVec::try_from(bytes).map_err(|_| Error::new())?;
 
         Ok(Self {
             bytes,
+            verify_chain_steps,
             #[cfg(feature = "verbose")]
-            hash_iterations: _hash_iterations,
+            hash_iterations: 0,
+            #[cfg(feature = "verbose")]
+            hash_operations: HashOperations::default(),
         })
     }
 }
@@ -152,7 +214,7 @@ impl AsRef<[u8]> for Signature {
 
 impl signature::Signature for Signature {
     fn from_bytes(bytes: &[u8]) -> Result<Self, Error> {
-        Signature::from_bytes_verbose(bytes, 0)
+        Signature::from_bytes_with_chain_steps(bytes, None)
     }
 }
 
@@ -189,7 +251,7 @@ mod tests {
     use crate::{keygen, HssParameter, LmotsAlgorithm, LmsAlgorithm, Sha256_256};
     use crate::{
         signature::{SignerMut, Verifier},
-        SigningKey, VerifierSignature, VerifyingKey,
+        Signature, SigningKey, VerifierSignature, VerifyingKey,
     };
 
     use crate::util::helper::test_helper::gen_random_seed;
@@ -235,6 +297,14 @@ mod tests {
 
         assert!(verifying_key.verify(&message, &signature).is_ok());
 
+        assert!(signature.verify_chain_steps.is_some());
+        assert!(
+            <Signature as signature::Signature>::from_bytes(signature.as_ref())
+                .unwrap()
+                .verify_chain_steps
+                .is_none()
+        );
+
         let ref_signature = VerifierSignature::from_ref(signature.as_ref()).unwrap();
 
         assert!(verifying_key.verify(&message, &ref_signature).is_ok());
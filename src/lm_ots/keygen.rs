@@ -2,7 +2,7 @@ use super::definitions::*;
 use super::parameters::LmotsParameter;
 use crate::constants::*;
 use crate::constants::{D_PBLC, MAX_HASH_CHAIN_COUNT, MAX_HASH_SIZE};
-use crate::hasher::HashChain;
+use crate::hasher::{do_hash_chains_batched, HashChain, HashOperations};
 use crate::Seed;
 use tinyvec::ArrayVec;
 
@@ -11,6 +11,7 @@ pub fn generate_private_key<H: HashChain>(
     lms_leaf_identifier: LmsLeafIdentifier,
     seed: Seed<H>,
     lmots_parameter: LmotsParameter<H>,
+    hash_operations: &mut HashOperations,
 ) -> LmotsPrivateKey<H> {
     let mut key = ArrayVec::new();
 
@@ -26,6 +27,11 @@ pub fn generate_private_key<H: HashChain>(
         key.push(hasher.finalize_reset());
     }
 
+    hash_operations.add_seed_derive(
+        lmots_parameter.get_hash_chain_count() as u64
+            * HashOperations::compressions::<H>(prng_len(seed.as_slice().len())),
+    );
+
     LmotsPrivateKey::new(
         lms_tree_identifier,
         lms_leaf_identifier,
@@ -34,7 +40,10 @@ pub fn generate_private_key<H: HashChain>(
     )
 }
 
-pub fn generate_public_key<H: HashChain>(private_key: &LmotsPrivateKey<H>) -> LmotsPublicKey<H> {
+pub fn generate_public_key<H: HashChain>(
+    private_key: &LmotsPrivateKey<H>,
+    hash_operations: &mut HashOperations,
+) -> LmotsPublicKey<H> {
     let lmots_parameter = &private_key.lmots_parameter;
     let mut hasher = lmots_parameter.get_hasher();
 
@@ -44,21 +53,15 @@ pub fn generate_public_key<H: HashChain>(private_key: &LmotsPrivateKey<H>) -> Lm
     let mut public_key_data: ArrayVec<[ArrayVec<[u8; MAX_HASH_SIZE]>; MAX_HASH_CHAIN_COUNT]> =
         ArrayVec::new();
 
-    for i in 0..lmots_parameter.get_hash_chain_count() as usize {
-        let mut hash_chain_data = H::prepare_hash_chain_data(
-            &private_key.lms_tree_identifier,
-            &private_key.lms_leaf_identifier,
-        );
-        let result = hasher.do_hash_chain(
-            &mut hash_chain_data,
-            i as u16,
-            key[i].as_slice(),
-            0,
-            hash_chain_count,
-        );
-
-        public_key_data.push(result);
-    }
+    do_hash_chains_batched(
+        &mut hasher,
+        &private_key.lms_tree_identifier,
+        &private_key.lms_leaf_identifier,
+        lmots_parameter.get_hash_chain_count(),
+        |i| (key[i as usize].as_slice(), 0, hash_chain_count),
+        |result| public_key_data.push(result),
+        hash_operations,
+    );
 
     hasher.update(&private_key.lms_tree_identifier);
     hasher.update(&private_key.lms_leaf_identifier);
@@ -69,6 +72,10 @@ pub fn generate_public_key<H: HashChain>(private_key: &LmotsPrivateKey<H>) -> Lm
     }
 
     let public_key = hasher.finalize();
+    hash_operations.add_leaf(HashOperations::compressions::<H>(
+        DOMAIN_PREFIX_LEN
+            + lmots_parameter.get_hash_chain_count() as usize * H::OUTPUT_SIZE as usize,
+    ));
 
     LmotsPublicKey::new(
         private_key.lms_tree_identifier,
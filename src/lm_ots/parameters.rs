@@ -132,6 +132,11 @@ impl<H: HashChain> LmotsParameter<H> {
         self.checksum_left_shift
     }
 
+    /// Hash chain steps of signing and verifying a LM-OTS signature together
+    pub fn get_max_hash_iterations(&self) -> u16 {
+        self.get_hash_chain_count() * ((1 << self.get_winternitz()) - 1)
+    }
+
     pub fn get_hash_function_output_size(&self) -> usize {
         Self::HASH_FUNCTION_OUTPUT_SIZE as usize
     }
@@ -1,6 +1,9 @@
 use crate::{
-    constants::{D_MESG, MAX_HASH_CHAIN_COUNT, MAX_HASH_SIZE, MAX_LMOTS_SIGNATURE_LENGTH},
-    hasher::HashChain,
+    constants::{
+        DOMAIN_PREFIX_LEN, D_MESG, D_RANDOMIZER, MAX_HASH_CHAIN_COUNT, MAX_HASH_SIZE,
+        MAX_LMOTS_SIGNATURE_LENGTH, RANDOMIZER_D, RANDOMIZER_PREFIX_LEN,
+    },
+    hasher::{do_hash_chains_batched, HashChain, HashOperations},
     lm_ots::parameters::LmotsAlgorithm,
     util::{coef::coef, helper::read_and_advance},
 };
@@ -10,17 +13,87 @@ use tinyvec::ArrayVec;
 
 #[cfg(feature = "fast_verify")]
 use {
-    crate::constants::{
-        FastVerifyCached, MAX_HASH_OPTIMIZATIONS, MAX_LMS_PUBLIC_KEY_LENGTH, THREADS,
-    },
-    core::convert::TryFrom,
+    crate::constants::THREADS,
     crossbeam::{channel::unbounded, scope},
-    rand::{rngs::OsRng, RngCore},
+    std::{time::Instant, vec::Vec},
+};
+#[cfg(feature = "fast_verify_core")]
+use {
+    crate::constants::{FastVerifyCached, MAX_HASH_OPTIMIZATIONS, MAX_LMS_PUBLIC_KEY_LENGTH},
+    core::{
+        convert::TryFrom,
+        sync::atomic::{AtomicBool, Ordering},
+    },
+    rand_core::CryptoRngCore,
 };
 
 use super::definitions::LmotsPrivateKey;
 use super::parameters::LmotsParameter;
 
+/**
+ * Effort spent on signing fast verifiable signatures (see [`crate::sign_mut_with_rng`]).
+ *
+ * The defaults are taken from `HBS_LMS_MAX_HASH_OPTIMIZATIONS` and `HBS_LMS_THREADS` at build
+ * time. Threads and deadlines are only available with the `fast_verify` feature, which requires
+ * `std`. As the fields depend on the features, options are built from the defaults with the
+ * `with_*` methods.
+ */
+#[cfg(feature = "fast_verify_core")]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct FastVerifyOptions {
+    /// Count of randomizers tried, split evenly between the threads
+    pub max_hash_optimizations: usize,
+    /// Count of threads trying randomizers in parallel
+    #[cfg(feature = "fast_verify")]
+    pub threads: usize,
+    /// Point in time after which no further randomizers are tried
+    #[cfg(feature = "fast_verify")]
+    pub deadline: Option<Instant>,
+    /// Stop as soon as a randomizer leaves at most this many hash chain steps to the verifier of
+    /// the LM-OTS signature, instead of keeping the best of all tried randomizers
+    pub target_verify_chain_steps: Option<u16>,
+}
+
+#[cfg(feature = "fast_verify_core")]
+impl Default for FastVerifyOptions {
+    fn default() -> Self {
+        Self {
+            max_hash_optimizations: MAX_HASH_OPTIMIZATIONS,
+            #[cfg(feature = "fast_verify")]
+            threads: THREADS,
+            #[cfg(feature = "fast_verify")]
+            deadline: None,
+            target_verify_chain_steps: None,
+        }
+    }
+}
+
+#[cfg(feature = "fast_verify_core")]
+impl FastVerifyOptions {
+    pub fn with_max_hash_optimizations(mut self, max_hash_optimizations: usize) -> Self {
+        self.max_hash_optimizations = max_hash_optimizations;
+        self
+    }
+
+    #[cfg(feature = "fast_verify")]
+    pub fn with_threads(mut self, threads: usize) -> Self {
+        self.threads = threads;
+        self
+    }
+
+    #[cfg(feature = "fast_verify")]
+    pub fn with_deadline(mut self, deadline: Instant) -> Self {
+        self.deadline = Some(deadline);
+        self
+    }
+
+    pub fn with_target_verify_chain_steps(mut self, target_verify_chain_steps: u16) -> Self {
+        self.target_verify_chain_steps = Some(target_verify_chain_steps);
+        self
+    }
+}
+
 #[derive(Debug, Default, Clone, PartialEq, Eq)]
 pub struct LmotsSignature<H: HashChain> {
     pub signature_randomizer: ArrayVec<[u8; MAX_HASH_SIZE]>,
@@ -60,7 +133,7 @@ impl<'a, H: HashChain> PartialEq<LmotsSignature<H>> for InMemoryLmotsSignature<'
 }
 
 impl<H: HashChain> LmotsSignature<H> {
-    fn calculate_message_hash(
+    pub fn calculate_message_hash(
         private_key: &LmotsPrivateKey<H>,
         signature_randomizer: &ArrayVec<[u8; MAX_HASH_SIZE]>,
         message: &[u8],
@@ -76,13 +149,15 @@ impl<H: HashChain> LmotsSignature<H> {
             .chain(message)
     }
 
-    #[cfg(feature = "fast_verify")]
+    #[cfg(feature = "fast_verify_core")]
     fn calculate_message_hash_fast_verify(
         private_key: &LmotsPrivateKey<H>,
         signature_randomizer: &mut ArrayVec<[u8; MAX_HASH_SIZE]>,
         message: Option<&[u8]>,
         message_mut: Option<&mut [u8]>,
-    ) -> H {
+        fast_verify_options: &FastVerifyOptions,
+        rng: &mut dyn CryptoRngCore,
+    ) -> (H, u64) {
         let lmots_parameter = private_key.lmots_parameter;
 
         let mut hasher = lmots_parameter
@@ -98,21 +173,93 @@ impl<H: HashChain> LmotsSignature<H> {
             hasher.update(signature_randomizer);
             hasher.update(message_mut);
 
-            optimize_message_hash(&hasher, &lmots_parameter, message_randomizer, None);
+            let trials = optimize_message_hash(
+                &hasher,
+                &lmots_parameter,
+                message_randomizer,
+                None,
+                fast_verify_options,
+                rng,
+            );
 
             hasher.update(message_randomizer);
+            (hasher, trials)
         } else {
-            optimize_message_hash(&hasher, &lmots_parameter, signature_randomizer, message);
+            let trials = optimize_message_hash(
+                &hasher,
+                &lmots_parameter,
+                signature_randomizer,
+                message,
+                fast_verify_options,
+                rng,
+            );
 
             hasher.update(signature_randomizer.as_slice());
             hasher.update(message.unwrap());
+            (hasher, trials)
         }
-        hasher
+    }
+
+    /// Replaces the randomizer by the one of its successors
+    /// `C_{i+1} = H(0^20 || D_RANDOMIZER || C_i)` with `D_RANDOMIZER = 0xf9f9`, which leaves the
+    /// fewest hash chain steps to the verifier. Unlike [`LmotsSignature::sign_fast_verify`] the
+    /// result only depends on the inputs, so it can be recomputed on every key expansion.
+    pub fn optimize_signature_randomizer(
+        private_key: &LmotsPrivateKey<H>,
+        signature_randomizer: &mut ArrayVec<[u8; MAX_HASH_SIZE]>,
+        message: &[u8],
+        trials: usize,
+        hash_operations: &mut HashOperations,
+    ) {
+        let lmots_parameter = private_key.lmots_parameter;
+        let fast_verify_cached = lmots_parameter.fast_verify_eval_init();
+
+        let mut prefix = [0u8; RANDOMIZER_PREFIX_LEN];
+        prefix[RANDOMIZER_D] = (D_RANDOMIZER >> 8) as u8;
+        prefix[RANDOMIZER_D + 1] = (D_RANDOMIZER & 0xff) as u8;
+
+        let mut trial_randomizer = *signature_randomizer;
+        let mut max_hash_iterations = 0;
+
+        for _ in 0..trials {
+            trial_randomizer = lmots_parameter
+                .get_hasher()
+                .chain(prefix)
+                .chain(trial_randomizer)
+                .finalize();
+
+            let message_hash: ArrayVec<[u8; MAX_HASH_SIZE]> =
+                LmotsSignature::<H>::calculate_message_hash(
+                    private_key,
+                    &trial_randomizer,
+                    message,
+                )
+                .finalize();
+
+            let hash_iterations =
+                lmots_parameter.fast_verify_eval(message_hash.as_slice(), &fast_verify_cached);
+
+            if hash_iterations > max_hash_iterations {
+                max_hash_iterations = hash_iterations;
+                *signature_randomizer = trial_randomizer;
+            }
+        }
+
+        hash_operations.add_seed_derive(
+            trials as u64
+                * HashOperations::compressions::<H>(
+                    RANDOMIZER_PREFIX_LEN + H::OUTPUT_SIZE as usize,
+                ),
+        );
+        hash_operations.add_message(
+            trials as u64 * HashOperations::compressions::<H>(message_hash_len::<H>(message.len())),
+        );
     }
 
     fn calculate_signature(
         private_key: &LmotsPrivateKey<H>,
         message_hash_with_checksum: &ArrayVec<[u8; MAX_HASH_SIZE + 2]>,
+        hash_operations: &mut HashOperations,
     ) -> ArrayVec<[ArrayVec<[u8; MAX_HASH_SIZE]>; MAX_HASH_CHAIN_COUNT]> {
         let lmots_parameter = private_key.lmots_parameter;
 
@@ -120,21 +267,22 @@ impl<H: HashChain> LmotsSignature<H> {
 
         let mut signature_data = ArrayVec::new();
 
-        for i in 0..lmots_parameter.get_hash_chain_count() {
-            let a = coef(
-                message_hash_with_checksum.as_slice(),
-                i,
-                lmots_parameter.get_winternitz(),
-            ) as usize;
-            let initial = private_key.key[i as usize];
-            let mut hash_chain_data = H::prepare_hash_chain_data(
-                &private_key.lms_tree_identifier,
-                &private_key.lms_leaf_identifier,
-            );
-            let result = hasher.do_hash_chain(&mut hash_chain_data, i, initial.as_slice(), 0, a);
-
-            signature_data.push(result);
-        }
+        do_hash_chains_batched(
+            &mut hasher,
+            &private_key.lms_tree_identifier,
+            &private_key.lms_leaf_identifier,
+            lmots_parameter.get_hash_chain_count(),
+            |i| {
+                let a = coef(
+                    message_hash_with_checksum.as_slice(),
+                    i,
+                    lmots_parameter.get_winternitz(),
+                ) as usize;
+                (private_key.key[i as usize].as_slice(), 0, a)
+            },
+            |result| signature_data.push(result),
+            hash_operations,
+        );
 
         signature_data
     }
@@ -143,32 +291,64 @@ impl<H: HashChain> LmotsSignature<H> {
         private_key: &LmotsPrivateKey<H>,
         signature_randomizer: &ArrayVec<[u8; MAX_HASH_SIZE]>,
         message: &[u8],
+        hash_operations: &mut HashOperations,
     ) -> Self {
         let mut hasher =
             LmotsSignature::<H>::calculate_message_hash(private_key, signature_randomizer, message);
-        LmotsSignature::<H>::sign_core(private_key, &mut hasher, signature_randomizer)
+        hash_operations.add_message(HashOperations::compressions::<H>(message_hash_len::<H>(
+            message.len(),
+        )));
+        LmotsSignature::<H>::sign_core(
+            private_key,
+            &mut hasher,
+            signature_randomizer,
+            hash_operations,
+        )
     }
 
-    #[cfg(feature = "fast_verify")]
+    #[cfg(feature = "fast_verify_core")]
     pub fn sign_fast_verify(
         private_key: &LmotsPrivateKey<H>,
         signature_randomizer: &mut ArrayVec<[u8; MAX_HASH_SIZE]>,
         message: Option<&[u8]>,
         message_mut: Option<&mut [u8]>,
+        fast_verify_options: &FastVerifyOptions,
+        rng: &mut dyn CryptoRngCore,
+        hash_operations: &mut HashOperations,
     ) -> Self {
-        let mut hasher = LmotsSignature::<H>::calculate_message_hash_fast_verify(
+        let message_len = message
+            .map(|message| message.len())
+            .or_else(|| message_mut.as_ref().map(|message_mut| message_mut.len()))
+            .unwrap_or_default();
+        let (mut hasher, trials) = LmotsSignature::<H>::calculate_message_hash_fast_verify(
             private_key,
             signature_randomizer,
             message,
             message_mut,
+            fast_verify_options,
+            rng,
+        );
+
+        // Every trial re-hashes the randomizer and the message, the final message hash included
+        hash_operations
+            .add_seed_derive(trials * HashOperations::compressions::<H>(H::OUTPUT_SIZE as usize));
+        hash_operations.add_message(
+            (trials + 1) * HashOperations::compressions::<H>(message_hash_len::<H>(message_len)),
         );
-        LmotsSignature::<H>::sign_core(private_key, &mut hasher, signature_randomizer)
+
+        LmotsSignature::<H>::sign_core(
+            private_key,
+            &mut hasher,
+            signature_randomizer,
+            hash_operations,
+        )
     }
 
-    fn sign_core(
+    pub fn sign_core(
         private_key: &LmotsPrivateKey<H>,
         hasher: &mut H,
         signature_randomizer: &ArrayVec<[u8; MAX_HASH_SIZE]>,
+        hash_operations: &mut HashOperations,
     ) -> Self {
         let lmots_parameter = private_key.lmots_parameter;
 
@@ -176,8 +356,11 @@ impl<H: HashChain> LmotsSignature<H> {
         let message_hash_with_checksum =
             lmots_parameter.append_checksum_to(message_hash.as_slice());
 
-        let signature_data =
-            LmotsSignature::<H>::calculate_signature(private_key, &message_hash_with_checksum);
+        let signature_data = LmotsSignature::<H>::calculate_signature(
+            private_key,
+            &message_hash_with_checksum,
+            hash_operations,
+        );
 
         let hash_iterations = (0..lmots_parameter.get_hash_chain_count()).fold(0, |sum, i| {
             sum + coef(
@@ -195,6 +378,11 @@ impl<H: HashChain> LmotsSignature<H> {
         }
     }
 
+    /// Hash chain steps left to the verifier of this signature
+    pub fn verify_chain_steps(&self) -> u16 {
+        self.lmots_parameter.get_max_hash_iterations() - self.hash_iterations
+    }
+
     pub fn to_binary_representation(&self) -> ArrayVec<[u8; MAX_LMOTS_SIGNATURE_LENGTH]> {
         let mut result = ArrayVec::new();
 
@@ -215,6 +403,24 @@ impl<H: HashChain> LmotsSignature<H> {
     }
 }
 
+// The hash iterations of the signer are unknown and left to the caller
+impl<'a, H: HashChain> From<&InMemoryLmotsSignature<'a, H>> for LmotsSignature<H> {
+    fn from(signature: &InMemoryLmotsSignature<'a, H>) -> Self {
+        let step = signature.lmots_parameter.get_hash_function_output_size();
+
+        Self {
+            signature_randomizer: signature.signature_randomizer.iter().copied().collect(),
+            signature_data: signature
+                .signature_data
+                .chunks_exact(step)
+                .map(|hash_chain_value| hash_chain_value.iter().copied().collect())
+                .collect(),
+            lmots_parameter: signature.lmots_parameter,
+            hash_iterations: 0,
+        }
+    }
+}
+
 impl<'a, H: HashChain> InMemoryLmotsSignature<'a, H> {
     pub fn new(data: &'a [u8]) -> Option<Self> {
         let mut index = 0;
@@ -247,70 +453,147 @@ impl<'a, H: HashChain> InMemoryLmotsSignature<'a, H> {
     }
 }
 
-#[cfg(feature = "fast_verify")]
+/// Length of I || q || D_MESG || C || message
+pub(crate) fn message_hash_len<H: HashChain>(message_len: usize) -> usize {
+    DOMAIN_PREFIX_LEN + H::OUTPUT_SIZE as usize + message_len
+}
+
+#[cfg(feature = "fast_verify_core")]
 fn optimize_message_hash<H: HashChain>(
     hasher: &H,
     lmots_parameter: &LmotsParameter<H>,
     randomizer: &mut [u8],
     message: Option<&[u8]>,
-) {
+    fast_verify_options: &FastVerifyOptions,
+    rng: &mut dyn CryptoRngCore,
+) -> u64 {
     let message = message
         .map(|message: &[u8]| ArrayVec::try_from(message).unwrap())
         .unwrap_or_default();
 
     assert_eq!(message, ArrayVec::new());
     let fast_verify_cached = lmots_parameter.fast_verify_eval_init();
+    let target_reached = &AtomicBool::new(false);
 
-    let rx = {
-        let (tx, rx) = unbounded();
+    let mut initial_randomizer = || {
+        let mut initial_randomizer = ArrayVec::from_array_len(
+            [0u8; MAX_HASH_SIZE],
+            lmots_parameter.get_hash_function_output_size(),
+        );
+        rng.fill_bytes(initial_randomizer.as_mut_slice());
+        initial_randomizer
+    };
+
+    // Threads without any trial are not started
+    #[cfg(feature = "fast_verify")]
+    let threads = fast_verify_options
+        .threads
+        .min(fast_verify_options.max_hash_optimizations);
+
+    #[cfg(feature = "fast_verify")]
+    if threads > 1 {
+        // The RNG of the caller is not shared, each thread gets its own initial randomizer
+        let initial_randomizers: Vec<_> = (0..threads).map(|_| initial_randomizer()).collect();
 
+        let (tx, rx) = unbounded();
         scope(|s| {
-            for _ in 0..THREADS {
+            for (thread_index, initial_randomizer) in initial_randomizers.into_iter().enumerate() {
                 let tx = tx.clone();
+                let fast_verify_cached = &fast_verify_cached;
+                let message = &message;
                 s.spawn(move |_| {
                     tx.send(thread_optimize_message_hash::<H>(
                         hasher,
                         lmots_parameter,
-                        &fast_verify_cached,
-                        &message,
+                        fast_verify_cached,
+                        message,
+                        thread_trials(
+                            fast_verify_options.max_hash_optimizations,
+                            threads,
+                            thread_index,
+                        ),
+                        fast_verify_options,
+                        target_reached,
+                        initial_randomizer,
                     ))
                     .unwrap()
                 });
             }
         })
         .unwrap();
-        rx
-    };
 
+        return select_best_randomizer(randomizer, rx.try_iter());
+    }
+
+    let result = thread_optimize_message_hash::<H>(
+        hasher,
+        lmots_parameter,
+        &fast_verify_cached,
+        &message,
+        fast_verify_options.max_hash_optimizations,
+        fast_verify_options,
+        target_reached,
+        initial_randomizer(),
+    );
+    select_best_randomizer(randomizer, core::iter::once(result))
+}
+
+/// Trials of the thread `thread_index`, the remainder is spread across the first threads
+#[cfg(feature = "fast_verify")]
+fn thread_trials(max_hash_optimizations: usize, threads: usize, thread_index: usize) -> usize {
+    max_hash_optimizations / threads + usize::from(thread_index < max_hash_optimizations % threads)
+}
+
+#[cfg(feature = "fast_verify_core")]
+fn select_best_randomizer(
+    randomizer: &mut [u8],
+    results: impl Iterator<Item = (u16, ArrayVec<[u8; MAX_HASH_SIZE]>, u64)>,
+) -> u64 {
     let mut max_hash_iterations = 0;
-    for (hash_iterations, trial_randomizer) in rx.iter() {
+    let mut trials = 0;
+    for (hash_iterations, trial_randomizer, thread_trials) in results {
+        trials += thread_trials;
         if hash_iterations > max_hash_iterations {
             max_hash_iterations = hash_iterations;
             randomizer.copy_from_slice(trial_randomizer.as_slice());
         }
     }
+    trials
 }
 
-#[cfg(feature = "fast_verify")]
+#[cfg(feature = "fast_verify_core")]
+#[allow(clippy::too_many_arguments)]
 fn thread_optimize_message_hash<H: HashChain>(
     hasher: &H,
     lmots_parameter: &LmotsParameter<H>,
     fast_verify_cached: &FastVerifyCached,
     message: &ArrayVec<[u8; MAX_LMS_PUBLIC_KEY_LENGTH]>,
-) -> (u16, ArrayVec<[u8; MAX_HASH_SIZE]>) {
+    trials: usize,
+    fast_verify_options: &FastVerifyOptions,
+    target_reached: &AtomicBool,
+    mut trial_randomizer: ArrayVec<[u8; MAX_HASH_SIZE]>,
+) -> (u16, ArrayVec<[u8; MAX_HASH_SIZE]>, u64) {
     let mut max_hash_iterations = 0;
+    let target_hash_iterations = fast_verify_options.target_verify_chain_steps.map(|target| {
+        lmots_parameter
+            .get_max_hash_iterations()
+            .saturating_sub(target)
+    });
 
-    let mut trial_randomizer: ArrayVec<[u8; MAX_HASH_SIZE]> = ArrayVec::new();
-    let mut randomizer: ArrayVec<[u8; MAX_HASH_SIZE]> = ArrayVec::new();
-
-    for _ in 0..lmots_parameter.get_hash_function_output_size() {
-        trial_randomizer.push(0u8);
-        randomizer.push(0u8);
-    }
+    let mut randomizer: ArrayVec<[u8; MAX_HASH_SIZE]> =
+        ArrayVec::from_array_len([0u8; MAX_HASH_SIZE], trial_randomizer.len());
 
-    OsRng.fill_bytes(trial_randomizer.as_mut_slice());
+    let mut trials_done = 0;
+    while trials_done < trials {
+        if target_reached.load(Ordering::Relaxed) {
+            break;
+        }
+        #[cfg(feature = "fast_verify")]
+        if matches!(fast_verify_options.deadline, Some(deadline) if Instant::now() >= deadline) {
+            break;
+        }
+        trials_done += 1;
 
-    for _ in 0..MAX_HASH_OPTIMIZATIONS / THREADS {
         trial_randomizer = lmots_parameter
             .get_hasher()
             .chain(trial_randomizer)
@@ -329,8 +612,12 @@ fn thread_optimize_message_hash<H: HashChain>(
             max_hash_iterations = hash_iterations;
             randomizer.copy_from_slice(trial_randomizer.as_slice());
         }
+
+        if matches!(target_hash_iterations, Some(target) if max_hash_iterations >= target) {
+            target_reached.store(true, Ordering::Relaxed);
+        }
     }
-    (max_hash_iterations, randomizer)
+    (max_hash_iterations, randomizer, trials_done as u64)
 }
 
 #[cfg(test)]
@@ -403,4 +690,25 @@ mod tests {
     generate_test!(lmots_shake256_n24_binary_representation_test, Shake256_192);
 
     generate_test!(lmots_shake256_n16_binary_representation_test, Shake256_256);
+
+    #[cfg(feature = "fast_verify")]
+    #[test]
+    fn thread_trials() {
+        use super::thread_trials;
+
+        assert_eq!(
+            (0..3).map(|i| thread_trials(10, 3, i)).collect::<Vec<_>>(),
+            [4, 3, 3]
+        );
+        assert_eq!(
+            (0..4).map(|i| thread_trials(12, 4, i)).collect::<Vec<_>>(),
+            [3, 3, 3, 3]
+        );
+        // Fewer trials than threads, the threads are reduced to the trial count beforehand
+        assert_eq!(
+            (0..2).map(|i| thread_trials(2, 2, i)).collect::<Vec<_>>(),
+            [1, 1]
+        );
+        assert_eq!((0..8).map(|i| thread_trials(5, 8, i)).sum::<usize>(), 5);
+    }
 }
@@ -1,11 +1,19 @@
-use core::{marker::PhantomData, usize};
+use core::marker::PhantomData;
 
 use tinyvec::ArrayVec;
 
 use crate::lm_ots::parameters::LmotsParameter;
-use crate::{constants::*, hasher::HashChain, util::coef::coef, LmotsAlgorithm};
-
-use super::{definitions::LmotsPublicKey, signing::InMemoryLmotsSignature};
+use crate::{
+    constants::*,
+    hasher::{do_hash_chains_batched, HashChain, HashOperations},
+    util::coef::coef,
+    LmotsAlgorithm,
+};
+
+use super::{
+    definitions::LmotsPublicKey,
+    signing::{message_hash_len, InMemoryLmotsSignature},
+};
 
 #[derive(Default)]
 struct HashChainArray<H: HashChain> {
@@ -63,7 +71,7 @@ impl<H: HashChain> HashChainArray<H> {
         } else if let Some(array_w2) = &self.array_w2 {
             array_w2.as_slice()
         } else {
-            return self.array_w1.as_ref().unwrap().as_slice();
+            self.array_w1.as_ref().unwrap().as_slice()
         }
     }
 }
@@ -83,48 +91,103 @@ pub fn verify_signature_inmemory<H: HashChain>(
         &public_key.lms_tree_identifier,
         u32::from_be_bytes(public_key.lms_leaf_identifier),
         message,
+        &mut HashOperations::default(),
     );
 
     public_key_candidate == public_key.key
 }
 
+pub fn message_hasher<H: HashChain>(
+    signature: &InMemoryLmotsSignature<'_, H>,
+    lms_tree_identifier: &[u8],
+    lms_leaf_identifier: u32,
+) -> H {
+    signature
+        .lmots_parameter
+        .get_hasher()
+        .chain(lms_tree_identifier)
+        .chain(lms_leaf_identifier.to_be_bytes())
+        .chain(D_MESG)
+        .chain(signature.signature_randomizer)
+}
+
+/// Hash chain steps of the verification of `signature` for the message hash `message_hash`
+pub fn verify_chain_steps<H: HashChain>(
+    signature: &InMemoryLmotsSignature<'_, H>,
+    message_hash: &[u8],
+) -> u32 {
+    let lmots_parameter = signature.lmots_parameter;
+    let message_hash_with_checksum = lmots_parameter.append_checksum_to(message_hash);
+    let max_w = (1u32 << lmots_parameter.get_winternitz()) - 1;
+
+    (0..lmots_parameter.get_hash_chain_count())
+        .map(|i| {
+            max_w
+                - coef(
+                    message_hash_with_checksum.as_slice(),
+                    i,
+                    lmots_parameter.get_winternitz(),
+                ) as u32
+        })
+        .sum()
+}
+
 pub fn generate_public_key_candiate<H: HashChain>(
     signature: &InMemoryLmotsSignature<'_, H>,
     lms_tree_identifier: &[u8],
     lms_leaf_identifier: u32,
     message: &[u8],
+    hash_operations: &mut HashOperations,
+) -> ArrayVec<[u8; MAX_HASH_SIZE]> {
+    let message_hash = message_hasher(signature, lms_tree_identifier, lms_leaf_identifier)
+        .chain(message)
+        .finalize();
+    hash_operations.add_message(HashOperations::compressions::<H>(message_hash_len::<H>(
+        message.len(),
+    )));
+
+    generate_public_key_candiate_from_message_hash(
+        signature,
+        lms_tree_identifier,
+        lms_leaf_identifier,
+        &message_hash,
+        hash_operations,
+    )
+}
+
+pub fn generate_public_key_candiate_from_message_hash<H: HashChain>(
+    signature: &InMemoryLmotsSignature<'_, H>,
+    lms_tree_identifier: &[u8],
+    lms_leaf_identifier: u32,
+    message_hash: &[u8],
+    hash_operations: &mut HashOperations,
 ) -> ArrayVec<[u8; MAX_HASH_SIZE]> {
     let lmots_parameter = signature.lmots_parameter;
     let mut hasher = lmots_parameter.get_hasher();
 
     let lms_leaf_identifier = lms_leaf_identifier.to_be_bytes();
 
-    hasher.update(lms_tree_identifier);
-    hasher.update(&lms_leaf_identifier);
-    hasher.update(&D_MESG);
-    hasher.update(signature.signature_randomizer);
-    hasher.update(message);
-
-    let message_hash = hasher.finalize_reset();
-    let message_hash_with_checksum = lmots_parameter.append_checksum_to(message_hash.as_slice());
+    let message_hash_with_checksum = lmots_parameter.append_checksum_to(message_hash);
 
     let mut hash_chain_array = HashChainArray::new(&lmots_parameter);
     let max_w = 2usize.pow(lmots_parameter.get_winternitz() as u32) - 1;
 
-    for i in 0..lmots_parameter.get_hash_chain_count() {
-        let a = coef(
-            message_hash_with_checksum.as_slice(),
-            i,
-            lmots_parameter.get_winternitz(),
-        ) as usize;
-
-        let initial = signature.get_signature_data(i as usize);
-        let mut hash_chain_data =
-            H::prepare_hash_chain_data(lms_tree_identifier, &lms_leaf_identifier);
-        let result = hasher.do_hash_chain(&mut hash_chain_data, i, initial, a, max_w);
-
-        hash_chain_array.push(&result);
-    }
+    do_hash_chains_batched(
+        &mut hasher,
+        lms_tree_identifier,
+        &lms_leaf_identifier,
+        lmots_parameter.get_hash_chain_count(),
+        |i| {
+            let a = coef(
+                message_hash_with_checksum.as_slice(),
+                i,
+                lmots_parameter.get_winternitz(),
+            ) as usize;
+            (signature.get_signature_data(i as usize), a, max_w)
+        },
+        |result| hash_chain_array.push(&result),
+        hash_operations,
+    );
 
     hasher.update(lms_tree_identifier);
     hasher.update(&lms_leaf_identifier);
@@ -132,6 +195,10 @@ pub fn generate_public_key_candiate<H: HashChain>(
     for hash_chain in hash_chain_array.as_slice() {
         hasher.update(hash_chain.as_slice());
     }
+    hash_operations.add_leaf(HashOperations::compressions::<H>(
+        DOMAIN_PREFIX_LEN
+            + lmots_parameter.get_hash_chain_count() as usize * H::OUTPUT_SIZE as usize,
+    ));
     hasher.finalize()
 }
 
@@ -167,9 +234,15 @@ mod tests {
                 ]);
 
                 let parameter = $type.construct_parameter::<$hash_chain>().unwrap();
-                let private_key =
-                    generate_private_key(lms_tree_identifier, lms_leaf_identifier, seed, parameter);
-                let public_key: LmotsPublicKey<$hash_chain> = generate_public_key(&private_key);
+                let private_key = generate_private_key(
+                    lms_tree_identifier,
+                    lms_leaf_identifier,
+                    seed,
+                    parameter,
+                    &mut Default::default(),
+                );
+                let public_key: LmotsPublicKey<$hash_chain> =
+                    generate_public_key(&private_key, &mut Default::default());
 
                 let mut message = [1, 3, 5, 9, 0];
                 let mut signature_randomizer = ArrayVec::from_array_len(
@@ -178,7 +251,12 @@ mod tests {
                 );
                 OsRng.fill_bytes(&mut signature_randomizer);
 
-                let signature = LmotsSignature::sign(&private_key, &signature_randomizer, &message);
+                let signature = LmotsSignature::sign(
+                    &private_key,
+                    &signature_randomizer,
+                    &message,
+                    &mut Default::default(),
+                );
 
                 let bin_representation = signature.to_binary_representation();
 
@@ -1,5 +1,5 @@
 use crate::constants::*;
-use crate::hasher::HashChain;
+use crate::hasher::{HashChain, HashOperations};
 use crate::lm_ots::definitions::LmotsPrivateKey;
 use crate::lm_ots::parameters::{LmotsAlgorithm, LmotsParameter};
 use crate::lms::helper::get_tree_element;
@@ -42,7 +42,10 @@ impl<H: HashChain> LmsPrivateKey<H> {
         }
     }
 
-    pub fn use_lmots_private_key(&mut self) -> Result<LmotsPrivateKey<H>, ()> {
+    pub fn use_lmots_private_key(
+        &mut self,
+        hash_operations: &mut HashOperations,
+    ) -> Result<LmotsPrivateKey<H>, ()> {
         let number_of_lm_ots_keys = self.lms_parameter.number_of_lm_ots_keys();
 
         if self.used_leafs_index as usize >= number_of_lm_ots_keys {
@@ -54,6 +57,7 @@ impl<H: HashChain> LmsPrivateKey<H> {
             self.used_leafs_index.to_be_bytes(),
             self.seed.clone(),
             self.lmots_parameter,
+            hash_operations,
         );
         self.used_leafs_index += 1;
 
@@ -73,8 +77,9 @@ impl<H: HashChain> LmsPublicKey<H> {
     pub fn new(
         private_key: &LmsPrivateKey<H>,
         aux_data: &mut Option<MutableExpandedAuxData>,
+        hash_operations: &mut HashOperations,
     ) -> Self {
-        let public_key = get_tree_element(1, private_key, aux_data);
+        let public_key = get_tree_element(1, private_key, aux_data, hash_operations);
 
         Self {
             key: public_key,
@@ -117,6 +122,17 @@ impl<'a, H: HashChain> PartialEq<LmsPublicKey<H>> for InMemoryLmsPublicKey<'a, H
     }
 }
 
+impl<'a, H: HashChain> From<&InMemoryLmsPublicKey<'a, H>> for LmsPublicKey<H> {
+    fn from(public_key: &InMemoryLmsPublicKey<'a, H>) -> Self {
+        Self {
+            key: public_key.key.iter().copied().collect(),
+            lms_tree_identifier: public_key.lms_tree_identifier.try_into().unwrap(),
+            lmots_parameter: public_key.lmots_parameter,
+            lms_parameter: public_key.lms_parameter,
+        }
+    }
+}
+
 impl<'a, H: HashChain> InMemoryLmsPublicKey<'a, H> {
     pub fn new(data: &'a [u8]) -> Option<Self> {
         // Parsing like desribed in 5.4.2
@@ -174,7 +190,7 @@ mod tests {
             LmsAlgorithm::construct_default_parameter(),
         );
 
-        let public_key = LmsPublicKey::new(&private_key, &mut None);
+        let public_key = LmsPublicKey::new(&private_key, &mut None, &mut Default::default());
 
         let serialized = public_key.to_binary_representation();
         let deserialized = InMemoryLmsPublicKey::new(serialized.as_slice())
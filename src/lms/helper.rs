@@ -1,7 +1,7 @@
 use tinyvec::ArrayVec;
 
-use crate::constants::{D_INTR, D_LEAF, MAX_HASH_SIZE};
-use crate::hasher::HashChain;
+use crate::constants::{DOMAIN_PREFIX_LEN, D_INTR, D_LEAF, MAX_HASH_SIZE};
+use crate::hasher::{HashChain, HashOperations};
 use crate::hss::aux::{hss_extract_aux_data, hss_save_aux_data, MutableExpandedAuxData};
 use crate::lm_ots;
 
@@ -11,6 +11,7 @@ pub fn get_tree_element<H: HashChain>(
     index: usize,
     private_key: &LmsPrivateKey<H>,
     aux_data: &mut Option<MutableExpandedAuxData>,
+    hash_operations: &mut HashOperations,
 ) -> ArrayVec<[u8; MAX_HASH_SIZE]> {
     // Check if we already have the value cached
     if let Some(aux_data) = aux_data {
@@ -31,16 +32,24 @@ pub fn get_tree_element<H: HashChain>(
             ((index - max_private_keys) as u32).to_be_bytes(),
             private_key.seed.clone(),
             private_key.lmots_parameter,
+            hash_operations,
         );
-        let lm_ots_public_key = lm_ots::keygen::generate_public_key(&lms_ots_private_key);
+        let lm_ots_public_key =
+            lm_ots::keygen::generate_public_key(&lms_ots_private_key, hash_operations);
+        hash_operations.add_leaf(HashOperations::compressions::<H>(
+            DOMAIN_PREFIX_LEN + H::OUTPUT_SIZE as usize,
+        ));
 
         hasher
             .chain(D_LEAF)
             .chain(lm_ots_public_key.key.as_slice())
             .finalize()
     } else {
-        let left = get_tree_element(2 * index, private_key, aux_data);
-        let right = get_tree_element(2 * index + 1, private_key, aux_data);
+        let left = get_tree_element(2 * index, private_key, aux_data, hash_operations);
+        let right = get_tree_element(2 * index + 1, private_key, aux_data, hash_operations);
+        hash_operations.add_interior_node(HashOperations::compressions::<H>(
+            DOMAIN_PREFIX_LEN + 2 * H::OUTPUT_SIZE as usize,
+        ));
 
         hasher
             .chain(D_INTR)
@@ -1,4 +1,4 @@
-use crate::hasher::HashChain;
+use crate::hasher::{HashChain, HashOperations};
 use crate::hss::aux::MutableExpandedAuxData;
 use crate::hss::parameter::HssParameter;
 use crate::hss::reference_impl_private_key::SeedAndLmsTreeIdentifier;
@@ -21,6 +21,7 @@ pub fn generate_key_pair<H: HashChain>(
     parameter: &HssParameter<H>,
     used_leafs_index: &u32,
     aux_data: &mut Option<MutableExpandedAuxData>,
+    hash_operations: &mut HashOperations,
 ) -> LmsKeyPair<H> {
     let lmots_parameter = parameter.get_lmots_parameter();
     let lms_parameter = parameter.get_lms_parameter();
@@ -32,7 +33,7 @@ pub fn generate_key_pair<H: HashChain>(
         *lmots_parameter,
         *lms_parameter,
     );
-    let public_key = LmsPublicKey::new(&private_key, aux_data);
+    let public_key = LmsPublicKey::new(&private_key, aux_data, hash_operations);
 
     LmsKeyPair {
         private_key,
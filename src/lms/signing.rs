@@ -1,7 +1,7 @@
 use crate::constants::{
     LmsLeafIdentifier, MAX_HASH_SIZE, MAX_LMS_SIGNATURE_LENGTH, MAX_TREE_HEIGHT,
 };
-use crate::hasher::HashChain;
+use crate::hasher::{HashChain, HashOperations};
 use crate::hss::aux::MutableExpandedAuxData;
 use crate::lm_ots;
 use crate::lm_ots::definitions::LmotsPrivateKey;
@@ -11,6 +11,8 @@ use crate::lm_ots::signing::LmotsSignature;
 use crate::lms::definitions::LmsPrivateKey;
 use crate::lms::parameters::LmsAlgorithm;
 use crate::util::helper::{read, read_and_advance};
+#[cfg(feature = "fast_verify_core")]
+use {crate::lm_ots::signing::FastVerifyOptions, rand_core::CryptoRngCore};
 
 use core::convert::TryInto;
 use tinyvec::ArrayVec;
@@ -65,6 +67,7 @@ impl<H: HashChain> LmsSignature<H> {
         lms_private_key: &mut LmsPrivateKey<H>,
         lm_ots_private_key: &LmotsPrivateKey<H>,
         aux_data: &mut Option<MutableExpandedAuxData>,
+        hash_operations: &mut HashOperations,
     ) -> Result<ArrayVec<[ArrayVec<[u8; MAX_HASH_SIZE]>; MAX_TREE_HEIGHT]>, ()> {
         let tree_height = lms_private_key.lms_parameter.get_tree_height();
         let signature_leaf_index = 2usize.pow(tree_height as u32)
@@ -74,33 +77,46 @@ impl<H: HashChain> LmsSignature<H> {
 
         for i in 0..tree_height.into() {
             let tree_index = (signature_leaf_index / (2usize.pow(i as u32))) ^ 0x1;
-            authentication_path.push(get_tree_element(tree_index, lms_private_key, aux_data));
+            authentication_path.push(get_tree_element(
+                tree_index,
+                lms_private_key,
+                aux_data,
+                hash_operations,
+            ));
         }
 
         Ok(authentication_path)
     }
 
-    #[cfg(feature = "fast_verify")]
+    #[cfg(feature = "fast_verify_core")]
+    #[allow(clippy::too_many_arguments)]
     pub fn sign_fast_verify(
         lms_private_key: &mut LmsPrivateKey<H>,
         message: Option<&[u8]>,
         message_mut: Option<&mut [u8]>,
         signature_randomizer: &mut ArrayVec<[u8; MAX_HASH_SIZE]>,
+        fast_verify_options: &FastVerifyOptions,
+        rng: &mut dyn CryptoRngCore,
         aux_data: &mut Option<MutableExpandedAuxData>,
+        hash_operations: &mut HashOperations,
     ) -> Result<LmsSignature<H>, ()> {
-        let lm_ots_private_key = lms_private_key.use_lmots_private_key()?;
+        let lm_ots_private_key = lms_private_key.use_lmots_private_key(hash_operations)?;
 
         let ots_signature = LmotsSignature::sign_fast_verify(
             &lm_ots_private_key,
             signature_randomizer,
             message,
             message_mut,
+            fast_verify_options,
+            rng,
+            hash_operations,
         );
 
         let authentication_path = LmsSignature::<H>::build_authentication_path(
             lms_private_key,
             &lm_ots_private_key,
             aux_data,
+            hash_operations,
         )?;
 
         let signature = LmsSignature {
@@ -118,26 +134,71 @@ impl<H: HashChain> LmsSignature<H> {
         message: &[u8],
         signature_randomizer: &ArrayVec<[u8; MAX_HASH_SIZE]>,
         aux_data: &mut Option<MutableExpandedAuxData>,
+        hash_operations: &mut HashOperations,
     ) -> Result<LmsSignature<H>, ()> {
-        let lm_ots_private_key = lms_private_key.use_lmots_private_key()?;
+        let prepared_signature = LmsSignature::prepare(lms_private_key, aux_data, hash_operations)?;
 
-        let ots_signature =
-            LmotsSignature::sign(&lm_ots_private_key, signature_randomizer, message);
+        let lmots_signature = LmotsSignature::sign(
+            &prepared_signature.lm_ots_private_key,
+            signature_randomizer,
+            message,
+            hash_operations,
+        );
+
+        Ok(prepared_signature.into_signature(lmots_signature))
+    }
+
+    /// Like [`LmsSignature::sign`], but with the randomizer replaced by the best of `trials`
+    /// deterministic successors (see [`LmotsSignature::optimize_signature_randomizer`])
+    pub fn sign_with_optimized_randomizer(
+        lms_private_key: &mut LmsPrivateKey<H>,
+        message: &[u8],
+        signature_randomizer: &ArrayVec<[u8; MAX_HASH_SIZE]>,
+        trials: usize,
+        aux_data: &mut Option<MutableExpandedAuxData>,
+        hash_operations: &mut HashOperations,
+    ) -> Result<LmsSignature<H>, ()> {
+        let prepared_signature = LmsSignature::prepare(lms_private_key, aux_data, hash_operations)?;
+
+        let mut signature_randomizer = *signature_randomizer;
+        LmotsSignature::optimize_signature_randomizer(
+            &prepared_signature.lm_ots_private_key,
+            &mut signature_randomizer,
+            message,
+            trials,
+            hash_operations,
+        );
+
+        let lmots_signature = LmotsSignature::sign(
+            &prepared_signature.lm_ots_private_key,
+            &signature_randomizer,
+            message,
+            hash_operations,
+        );
+
+        Ok(prepared_signature.into_signature(lmots_signature))
+    }
+
+    /// Reserves the next leaf and computes everything of the signature except the LM-OTS part
+    pub fn prepare(
+        lms_private_key: &mut LmsPrivateKey<H>,
+        aux_data: &mut Option<MutableExpandedAuxData>,
+        hash_operations: &mut HashOperations,
+    ) -> Result<PreparedLmsSignature<H>, ()> {
+        let lm_ots_private_key = lms_private_key.use_lmots_private_key(hash_operations)?;
 
         let authentication_path = LmsSignature::<H>::build_authentication_path(
             lms_private_key,
             &lm_ots_private_key,
             aux_data,
+            hash_operations,
         )?;
 
-        let signature = LmsSignature {
-            lms_leaf_identifier: lm_ots_private_key.lms_leaf_identifier,
-            lmots_signature: ots_signature,
+        Ok(PreparedLmsSignature {
+            lm_ots_private_key,
             authentication_path,
             lms_parameter: lms_private_key.lms_parameter,
-        };
-
-        Ok(signature)
+        })
     }
 
     pub fn to_binary_representation(&self) -> ArrayVec<[u8; MAX_LMS_SIGNATURE_LENGTH]> {
@@ -159,6 +220,66 @@ impl<H: HashChain> LmsSignature<H> {
     }
 }
 
+/// LMS signature of a reserved leaf, which still lacks the LM-OTS signature of the message
+pub struct PreparedLmsSignature<H: HashChain> {
+    lm_ots_private_key: LmotsPrivateKey<H>,
+    authentication_path: ArrayVec<[ArrayVec<[u8; MAX_HASH_SIZE]>; MAX_TREE_HEIGHT]>,
+    lms_parameter: LmsParameter<H>,
+}
+
+impl<H: HashChain> PreparedLmsSignature<H> {
+    /// Hasher with `I || q || D_MESG || C` already absorbed, to be fed with the message
+    pub fn message_hasher(&self, signature_randomizer: &ArrayVec<[u8; MAX_HASH_SIZE]>) -> H {
+        LmotsSignature::<H>::calculate_message_hash(
+            &self.lm_ots_private_key,
+            signature_randomizer,
+            &[],
+        )
+    }
+
+    pub fn finalize(
+        self,
+        message_hasher: &mut H,
+        signature_randomizer: &ArrayVec<[u8; MAX_HASH_SIZE]>,
+        hash_operations: &mut HashOperations,
+    ) -> LmsSignature<H> {
+        let lmots_signature = LmotsSignature::sign_core(
+            &self.lm_ots_private_key,
+            message_hasher,
+            signature_randomizer,
+            hash_operations,
+        );
+
+        self.into_signature(lmots_signature)
+    }
+
+    fn into_signature(self, lmots_signature: LmotsSignature<H>) -> LmsSignature<H> {
+        LmsSignature {
+            lms_leaf_identifier: self.lm_ots_private_key.lms_leaf_identifier,
+            lmots_signature,
+            authentication_path: self.authentication_path,
+            lms_parameter: self.lms_parameter,
+        }
+    }
+}
+
+impl<'a, H: HashChain> From<&InMemoryLmsSignature<'a, H>> for LmsSignature<H> {
+    fn from(signature: &InMemoryLmsSignature<'a, H>) -> Self {
+        let step = signature.lms_parameter.get_hash_function_output_size();
+
+        Self {
+            lms_leaf_identifier: signature.lms_leaf_identifier.to_be_bytes(),
+            lmots_signature: (&signature.lmots_signature).into(),
+            authentication_path: signature
+                .authentication_path
+                .chunks_exact(step)
+                .map(|node| node.iter().copied().collect())
+                .collect(),
+            lms_parameter: signature.lms_parameter,
+        }
+    }
+}
+
 impl<'a, H: HashChain> InMemoryLmsSignature<'a, H> {
     pub fn new(data: &'a [u8]) -> Option<Self> {
         // Parsing like 5.4.2 Algorithm 6a
@@ -170,8 +291,7 @@ impl<'a, H: HashChain> InMemoryLmsSignature<'a, H> {
         // LMOTS Signature consists of LMOTS parameter, signature randomizer & signature data
         let lmots_parameter = LmotsAlgorithm::get_from_type::<H>(u32::from_be_bytes(
             read(data, 4, &index).try_into().unwrap(),
-        ))
-        .unwrap();
+        ))?;
         let lmots_signature = lm_ots::signing::InMemoryLmotsSignature::new(read_and_advance(
             data,
             (4 + H::OUTPUT_SIZE * (1 + lmots_parameter.get_hash_chain_count())) as usize,
@@ -239,9 +359,14 @@ mod tests {
         let mut signature_randomizer = ArrayVec::from([0u8; 32]);
         OsRng.fill_bytes(&mut signature_randomizer);
 
-        let signature =
-            LmsSignature::sign(&mut private_key, message, &signature_randomizer, &mut None)
-                .expect("Signing must succeed.");
+        let signature = LmsSignature::sign(
+            &mut private_key,
+            message,
+            &signature_randomizer,
+            &mut None,
+            &mut Default::default(),
+        )
+        .expect("Signing must succeed.");
 
         let binary = signature.to_binary_representation();
 
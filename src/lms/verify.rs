@@ -1,28 +1,55 @@
 use tinyvec::ArrayVec;
 
-use crate::constants::{D_INTR, D_LEAF, MAX_HASH_SIZE};
-use crate::hasher::HashChain;
+use crate::constants::{DOMAIN_PREFIX_LEN, D_INTR, D_LEAF, MAX_HASH_SIZE};
+use crate::hasher::{HashChain, HashOperations};
 use crate::lm_ots;
+use crate::lm_ots::signing::message_hash_len;
 use crate::util::helper::is_odd;
 
 use super::definitions::InMemoryLmsPublicKey;
 use super::signing::InMemoryLmsSignature;
 
+/// Returns the hash chain steps the verification took
 pub fn verify<'a, H: HashChain>(
     signature: &InMemoryLmsSignature<'a, H>,
     public_key: &InMemoryLmsPublicKey<'a, H>,
     message: &[u8],
-) -> Result<(), ()> {
+    hash_operations: &mut HashOperations,
+) -> Result<u32, ()> {
+    let message_hash = lm_ots::verify::message_hasher(
+        &signature.lmots_signature,
+        public_key.lms_tree_identifier,
+        signature.lms_leaf_identifier,
+    )
+    .chain(message)
+    .finalize();
+    hash_operations.add_message(HashOperations::compressions::<H>(message_hash_len::<H>(
+        message.len(),
+    )));
+
+    verify_message_hash(signature, public_key, &message_hash, hash_operations)
+}
+
+pub fn verify_message_hash<'a, H: HashChain>(
+    signature: &InMemoryLmsSignature<'a, H>,
+    public_key: &InMemoryLmsPublicKey<'a, H>,
+    message_hash: &[u8],
+    hash_operations: &mut HashOperations,
+) -> Result<u32, ()> {
     if signature.lmots_signature.lmots_parameter != public_key.lmots_parameter
         || signature.lms_parameter != public_key.lms_parameter
     {
         return Err(());
     }
 
-    let public_key_canditate = generate_public_key_candiate(signature, public_key, message)?;
+    let public_key_canditate =
+        generate_public_key_candiate(signature, public_key, message_hash, hash_operations)?;
 
     if public_key_canditate.as_slice() == public_key.key {
-        Ok(())
+        Ok(lm_ots::verify::verify_chain_steps(
+            &signature.lmots_signature,
+            message_hash,
+        ))
     } else {
         Err(())
     }
@@ -31,7 +58,8 @@ pub fn verify<'a, H: HashChain>(
 fn generate_public_key_candiate<'a, H: HashChain>(
     signature: &InMemoryLmsSignature<'a, H>,
     public_key: &InMemoryLmsPublicKey<'a, H>,
-    message: &[u8],
+    message_hash: &[u8],
+    hash_operations: &mut HashOperations,
 ) -> Result<ArrayVec<[u8; MAX_HASH_SIZE]>, ()> {
     let leafs = signature.lms_parameter.number_of_lm_ots_keys() as u32;
 
@@ -40,11 +68,12 @@ fn generate_public_key_candiate<'a, H: HashChain>(
         return Err(());
     }
 
-    let ots_public_key_canditate = lm_ots::verify::generate_public_key_candiate(
+    let ots_public_key_canditate = lm_ots::verify::generate_public_key_candiate_from_message_hash(
         &signature.lmots_signature,
         public_key.lms_tree_identifier,
         signature.lms_leaf_identifier,
-        message,
+        message_hash,
+        hash_operations,
     );
 
     let mut node_num: u32 = leafs + signature.lms_leaf_identifier;
@@ -55,6 +84,9 @@ fn generate_public_key_candiate<'a, H: HashChain>(
     hasher.update(&D_LEAF);
     hasher.update(ots_public_key_canditate.as_slice());
     let mut temp = hasher.finalize_reset();
+    hash_operations.add_leaf(HashOperations::compressions::<H>(
+        DOMAIN_PREFIX_LEN + H::OUTPUT_SIZE as usize,
+    ));
 
     let mut i = 0;
     let mut nodes: [&[u8]; 2];
@@ -75,6 +107,9 @@ fn generate_public_key_candiate<'a, H: HashChain>(
         hasher.update(nodes[0]);
         hasher.update(nodes[1]);
         temp = hasher.finalize_reset();
+        hash_operations.add_interior_node(HashOperations::compressions::<H>(
+            DOMAIN_PREFIX_LEN + 2 * H::OUTPUT_SIZE as usize,
+        ));
     }
 
     Ok(temp)
@@ -110,7 +145,8 @@ mod tests {
             LmsAlgorithm::construct_default_parameter(),
         );
 
-        let public_key = LmsPublicKey::new(&private_key, &mut None).to_binary_representation();
+        let public_key = LmsPublicKey::new(&private_key, &mut None, &mut Default::default())
+            .to_binary_representation();
 
         let public_key = InMemoryLmsPublicKey::<Hasher>::new(public_key.as_slice()).unwrap();
 
@@ -124,6 +160,7 @@ mod tests {
             &first_message,
             &signature_randomizer,
             &mut None,
+            &mut Default::default(),
         )
         .unwrap()
         .to_binary_representation();
@@ -132,6 +169,7 @@ mod tests {
             &second_message,
             &signature_randomizer,
             &mut None,
+            &mut Default::default(),
         )
         .unwrap()
         .to_binary_representation();
@@ -139,12 +177,36 @@ mod tests {
         let first_signature = InMemoryLmsSignature::new(first_signature.as_slice()).unwrap();
         let second_signature = InMemoryLmsSignature::new(second_signature.as_slice()).unwrap();
 
-        assert!(super::verify(&first_signature, &public_key, &first_message).is_ok());
+        assert!(super::verify(
+            &first_signature,
+            &public_key,
+            &first_message,
+            &mut Default::default()
+        )
+        .is_ok());
         first_message[5] = 13;
-        assert!(super::verify(&first_signature, &public_key, &first_message).is_err());
+        assert!(super::verify(
+            &first_signature,
+            &public_key,
+            &first_message,
+            &mut Default::default()
+        )
+        .is_err());
 
-        assert!(super::verify(&second_signature, &public_key, &second_message).is_ok());
+        assert!(super::verify(
+            &second_signature,
+            &public_key,
+            &second_message,
+            &mut Default::default()
+        )
+        .is_ok());
         second_message[4] = 13;
-        assert!(super::verify(&second_signature, &public_key, &second_message).is_err());
+        assert!(super::verify(
+            &second_signature,
+            &public_key,
+            &second_message,
+            &mut Default::default()
+        )
+        .is_err());
     }
 }
@@ -72,7 +72,7 @@ fn create_signature_with_own_implementation() {
     own_signing(
         &tempdir,
         &mut message_data,
-        signing_key.as_mut_slice(),
+        signing_key.expose_secret_mut(),
         aux_slice,
     );
 
@@ -81,7 +81,7 @@ fn create_signature_with_own_implementation() {
     own_signing(
         &tempdir,
         &mut message_data,
-        signing_key.as_mut_slice(),
+        signing_key.expose_secret_mut(),
         aux_slice,
     );
 
@@ -125,7 +125,7 @@ fn should_produce_same_private_key() {
     let ref_signing_key = read_private_key(path);
     let ref_verifying_key = read_public_key(path);
 
-    assert!(ref_signing_key == sk.as_slice());
+    assert!(ref_signing_key == sk.expose_secret());
     assert!(ref_verifying_key == vk.as_slice());
 }
 